@@ -22,6 +22,9 @@ pub enum FromBase58CheckError {
     /// Prefix does not match expected.
     #[error("incorrect base58 prefix for hash type")]
     IncorrectBase58Prefix,
+    /// Prefix does not match any hash type.
+    #[error("unknown base58 prefix")]
+    UnknownBase58Prefix,
 }
 
 /// Create double hash of given binary data
@@ -58,6 +61,40 @@ impl ToBase58Check for [u8] {
     }
 }
 
+// Largest prefixed data encoded on the stack, enough for every hash type.
+const STACK_DATA_SIZE: usize = 128;
+
+// Base58 takes less than 1.37 characters per byte.
+const STACK_ENCODED_SIZE: usize = 2 * (STACK_DATA_SIZE + 4);
+
+/// Writes the base58check encoding of `prefix` followed by `data`.
+///
+/// Unlike [ToBase58Check], this does not allocate, for data of the size of
+/// hashes.
+pub fn write_base58check<W>(prefix: &[u8], data: &[u8], out: &mut W) -> std::fmt::Result
+where
+    W: std::fmt::Write + ?Sized,
+{
+    let len = prefix.len() + data.len();
+    if len > STACK_DATA_SIZE {
+        return out.write_str(&[prefix, data].concat().to_base58check());
+    }
+
+    let mut payload = [0; STACK_DATA_SIZE + 4];
+    payload[..prefix.len()].copy_from_slice(prefix);
+    payload[prefix.len()..len].copy_from_slice(data);
+    let checksum = double_sha256(&payload[..len]);
+    payload[len..len + 4].copy_from_slice(&checksum[..4]);
+
+    let mut encoded = [0; STACK_ENCODED_SIZE];
+    let encoded_len = bs58::encode(&payload[..len + 4])
+        .onto(&mut encoded[..])
+        .map_err(|_| std::fmt::Error)?;
+    // The base58 alphabet is ASCII.
+    let encoded = std::str::from_utf8(&encoded[..encoded_len]).map_err(|_| std::fmt::Error)?;
+    out.write_str(encoded)
+}
+
 impl FromBase58Check for str {
     fn from_base58check(&self) -> Result<Vec<u8>, FromBase58CheckError> {
         match bs58::decode(self).into_vec() {
@@ -85,6 +122,28 @@ impl FromBase58Check for str {
     }
 }
 
+/// Decodes base58check encoded `data`, made of `prefix` followed by `size`
+/// bytes, which are returned.
+pub fn from_base58check_with_prefix(
+    data: &str,
+    prefix: &[u8],
+    size: usize,
+) -> Result<Vec<u8>, FromBase58CheckError> {
+    let mut bytes = data.from_base58check()?;
+    if !bytes.starts_with(prefix) {
+        return Err(FromBase58CheckError::IncorrectBase58Prefix);
+    }
+    if bytes.len() != prefix.len() + size {
+        return Err(FromBase58CheckError::MismatchedLength {
+            expected: prefix.len() + size,
+            actual: bytes.len(),
+        });
+    }
+
+    bytes.drain(..prefix.len());
+    Ok(bytes)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,6 +157,24 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_write() -> Result<(), anyhow::Error> {
+        let data = hex::decode("8eceda2f")?;
+        for split in 0..=data.len() {
+            let mut encoded = String::new();
+            write_base58check(&data[..split], &data[split..], &mut encoded)?;
+            assert_eq!("QtRAcc9FSRg", encoded);
+        }
+
+        // Longer than the stack buffer.
+        let data = [0xab; 300];
+        let mut encoded = String::new();
+        write_base58check(&data[..5], &data[5..], &mut encoded)?;
+        assert_eq!(data.to_base58check(), encoded);
+
+        Ok(())
+    }
+
     #[test]
     fn test_decode() -> Result<(), anyhow::Error> {
         let decoded = "QtRAcc9FSRg".from_base58check()?;
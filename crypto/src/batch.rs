@@ -0,0 +1,396 @@
+// SPDX-FileCopyrightText: 2024 Trilitech <contact@trili.tech>
+//
+// SPDX-License-Identifier: MIT
+
+//! Batch signature verification.
+//!
+//! Ed25519 signatures are verified together with `ed25519-dalek` batch
+//! verification, and BLS signatures with a single multi-pairing. Other
+//! signatures are verified one at a time. When a batch fails, its entries are
+//! verified one at a time, to report which ones are invalid.
+//!
+//! With the `parallel` feature, verification is spread over the `rayon`
+//! thread pool.
+
+use thiserror::Error;
+
+use crate::hash::Ed25519Signature;
+use crate::public_key::PublicKey;
+use crate::signature::Signature;
+use crate::PublicKeySignatureVerifier;
+
+/// Error of batch verification.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid signatures at indices {invalid:?}")]
+pub struct BatchVerificationError {
+    /// Indices of the invalid entries of the batch, in increasing order.
+    pub invalid: Vec<usize>,
+}
+
+/// Verify a batch of signatures, each of its own message.
+///
+/// Signatures are checked as by [PublicKeySignatureVerifier::verify_signature]:
+/// ed25519 entries on which batch and strict verification could disagree, such
+/// as those whose `R` or public key is of small order, not canonically encoded
+/// or has a torsion component, are verified one at a time instead of batched.
+pub fn verify_batch(batch: &[(PublicKey, Signature, &[u8])]) -> Result<(), BatchVerificationError> {
+    let mut ed25519 = Vec::new();
+    let mut bls = Vec::new();
+    let mut others = Vec::new();
+
+    for (i, (pk, _, _)) in batch.iter().enumerate() {
+        match pk {
+            PublicKey::Ed25519(_) => ed25519.push(i),
+            PublicKey::Bls(_) => bls.push(i),
+            PublicKey::Secp256k1(_) | PublicKey::P256(_) => others.push(i),
+        }
+    }
+
+    let (mut invalid, (ed25519_invalid, bls_invalid)) = join(
+        || verify_each(batch, &others),
+        || join(|| verify_ed25519(batch, ed25519), || verify_bls(batch, bls)),
+    );
+
+    invalid.extend(ed25519_invalid);
+    invalid.extend(bls_invalid);
+
+    if invalid.is_empty() {
+        Ok(())
+    } else {
+        invalid.sort_unstable();
+        Err(BatchVerificationError { invalid })
+    }
+}
+
+fn verify_one((pk, sig, msg): &(PublicKey, Signature, &[u8])) -> bool {
+    matches!(pk.verify_signature(sig, msg), Ok(true))
+}
+
+// Indices of the invalid entries, verified one at a time.
+fn verify_each(batch: &[(PublicKey, Signature, &[u8])], indices: &[usize]) -> Vec<usize> {
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+
+        indices
+            .par_iter()
+            .copied()
+            .filter(|i| !verify_one(&batch[*i]))
+            .collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        indices
+            .iter()
+            .copied()
+            .filter(|i| !verify_one(&batch[*i]))
+            .collect()
+    }
+}
+
+fn verify_ed25519(batch: &[(PublicKey, Signature, &[u8])], indices: Vec<usize>) -> Vec<usize> {
+    let mut unbatched = Vec::new();
+    let mut batched = Vec::with_capacity(indices.len());
+    let mut public_keys = Vec::with_capacity(indices.len());
+    let mut signatures = Vec::with_capacity(indices.len());
+    let mut payloads = Vec::with_capacity(indices.len());
+
+    for i in indices {
+        let (pk, sig, msg) = &batch[i];
+        let public_key = match pk {
+            PublicKey::Ed25519(pk) => ed25519_dalek::VerifyingKey::try_from(pk).ok(),
+            _ => None,
+        };
+        let signature = Ed25519Signature::try_from(sig.clone())
+            .ok()
+            .and_then(|sig| <[u8; 64]>::try_from(sig.as_ref()).ok())
+            .map(|bytes| ed25519_dalek::Signature::from_bytes(&bytes));
+
+        match (public_key, signature) {
+            (Some(public_key), Some(signature)) if is_batchable(&public_key, &signature) => {
+                batched.push(i);
+                public_keys.push(public_key);
+                signatures.push(signature);
+                payloads.push(crate::blake2b::digest_256(msg));
+            }
+            _ => unbatched.push(i),
+        }
+    }
+
+    let mut invalid = verify_each(batch, &unbatched);
+
+    let payloads = payloads.iter().map(|p| &p[..]).collect::<Vec<_>>();
+    if !batched.is_empty()
+        && ed25519_dalek::verify_batch(&payloads, &signatures, &public_keys).is_err()
+    {
+        invalid.extend(verify_each(batch, &batched));
+    }
+    invalid
+}
+
+// Batch verification agrees with strict verification only when `R` is the
+// canonical encoding of a point of large order and neither `R` nor `A` has a
+// torsion component, which the batch equation does not multiply away.
+fn is_batchable(
+    public_key: &ed25519_dalek::VerifyingKey,
+    signature: &ed25519_dalek::Signature,
+) -> bool {
+    use curve25519_dalek::edwards::CompressedEdwardsY;
+
+    let a = CompressedEdwardsY(public_key.to_bytes()).decompress();
+    let r_bytes = CompressedEdwardsY(*signature.r_bytes());
+    match (a, r_bytes.decompress()) {
+        (Some(a), Some(r)) => {
+            !a.is_small_order()
+                && a.is_torsion_free()
+                && !r.is_small_order()
+                && r.is_torsion_free()
+                && r.compress() == r_bytes
+        }
+        _ => false,
+    }
+}
+
+#[cfg(any(feature = "bls", feature = "bls-pure"))]
+fn verify_bls(batch: &[(PublicKey, Signature, &[u8])], indices: Vec<usize>) -> Vec<usize> {
+    use crate::hash::BlsSignature;
+
+    let mut invalid = Vec::new();
+    let mut batched = Vec::with_capacity(indices.len());
+    let mut signatures = Vec::with_capacity(indices.len());
+
+    for i in indices {
+        match BlsSignature::try_from(batch[i].1.clone()) {
+            Ok(signature) => {
+                batched.push(i);
+                signatures.push(signature);
+            }
+            Err(_) => invalid.push(i),
+        }
+    }
+
+    let entries = batched
+        .iter()
+        .zip(signatures.iter())
+        .filter_map(|(i, sig)| match &batch[*i] {
+            (PublicKey::Bls(pk), _, msg) => Some((pk, sig, *msg)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if !matches!(crate::bls::verify_batch(&entries), Ok(true)) {
+        invalid.extend(verify_each(batch, &batched));
+    }
+    invalid
+}
+
+#[cfg(not(any(feature = "bls", feature = "bls-pure")))]
+fn verify_bls(batch: &[(PublicKey, Signature, &[u8])], indices: Vec<usize>) -> Vec<usize> {
+    verify_each(batch, &indices)
+}
+
+#[cfg(feature = "parallel")]
+fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    rayon::join(a, b)
+}
+
+#[cfg(not(feature = "parallel"))]
+fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA,
+    B: FnOnce() -> RB,
+{
+    (a(), b())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::secret_key::{Curve, SecretKey};
+
+    fn signed_batch(curves: &[Curve], n: usize) -> Vec<(PublicKey, Signature, Vec<u8>)> {
+        let mut rng = rand::thread_rng();
+
+        (0..n)
+            .map(|i| {
+                let curve = curves[i % curves.len()];
+                let sk = SecretKey::generate(curve, &mut rng).unwrap();
+                let msg = format!("message {}", i).into_bytes();
+                let sig = sk.sign(&msg).unwrap();
+                (sk.public_key().unwrap(), sig, msg)
+            })
+            .collect()
+    }
+
+    fn as_batch(batch: &[(PublicKey, Signature, Vec<u8>)]) -> Vec<(PublicKey, Signature, &[u8])> {
+        batch
+            .iter()
+            .map(|(pk, sig, msg)| (pk.clone(), sig.clone(), msg.as_slice()))
+            .collect()
+    }
+
+    fn curves() -> Vec<Curve> {
+        let mut curves = vec![Curve::Ed25519, Curve::Secp256k1, Curve::P256];
+        if cfg!(any(feature = "bls", feature = "bls-pure")) {
+            curves.push(Curve::Bls);
+        }
+        curves
+    }
+
+    #[test]
+    fn verify_valid_batch() {
+        assert_eq!(Ok(()), verify_batch(&[]));
+
+        let batch = signed_batch(&curves(), 16);
+
+        assert_eq!(Ok(()), verify_batch(&as_batch(&batch)));
+    }
+
+    #[test]
+    fn report_invalid_entries() {
+        let batch = signed_batch(&curves(), 16);
+        let mut batch = as_batch(&batch);
+
+        // Wrong message.
+        batch[1].2 = b"tampered";
+        batch[4].2 = b"tampered";
+        batch[6].2 = b"tampered";
+        // Signature of another key.
+        batch[11].1 = batch[15].1.clone();
+
+        assert_eq!(
+            Err(BatchVerificationError {
+                invalid: vec![1, 4, 6, 11]
+            }),
+            verify_batch(&batch)
+        );
+    }
+
+    #[test]
+    fn report_mismatched_signature_kind() {
+        let batch = signed_batch(&[Curve::Ed25519, Curve::Secp256k1], 2);
+        let mut batch = as_batch(&batch);
+
+        let sig = batch[0].1.clone();
+        batch[0].1 = batch[1].1.clone();
+        batch[1].1 = sig;
+
+        assert_eq!(
+            Err(BatchVerificationError {
+                invalid: vec![0, 1]
+            }),
+            verify_batch(&batch)
+        );
+    }
+
+    #[test]
+    fn report_small_order_r() {
+        use crate::hash::{HashTrait, PublicKeyEd25519};
+        use curve25519_dalek::scalar::Scalar;
+        use sha2::{Digest, Sha512};
+
+        let sk = ed25519_dalek::SigningKey::from_bytes(&[7; 32]);
+        let pk = sk.verifying_key();
+        let msg = b"message";
+        let payload = crate::blake2b::digest_256(msg);
+
+        // R is the identity and s = k * a, which satisfies the cofactored
+        // equation of batch verification, whatever the message.
+        let mut r = [0; 32];
+        r[0] = 1;
+        let k = Sha512::new()
+            .chain(r)
+            .chain(pk.as_bytes())
+            .chain(payload)
+            .finalize();
+        let mut wide = [0; 64];
+        wide.copy_from_slice(&k);
+        let s = Scalar::from_bytes_mod_order_wide(&wide) * sk.to_scalar();
+
+        let mut bytes = [0; 64];
+        bytes[..32].copy_from_slice(&r);
+        bytes[32..].copy_from_slice(s.as_bytes());
+        let signature = ed25519_dalek::Signature::from_bytes(&bytes);
+        assert!(ed25519_dalek::verify_batch(&[payload.as_slice()], &[signature], &[pk]).is_ok());
+
+        let entry = (
+            PublicKey::Ed25519(PublicKeyEd25519::try_from_bytes(pk.as_bytes()).unwrap()),
+            Signature::try_from(bytes.to_vec()).unwrap(),
+            &msg[..],
+        );
+        assert!(!verify_one(&entry));
+
+        let valid = signed_batch(&[Curve::Ed25519], 2);
+        let mut batch = as_batch(&valid);
+        batch.insert(1, entry);
+        assert_eq!(
+            Err(BatchVerificationError { invalid: vec![1] }),
+            verify_batch(&batch)
+        );
+    }
+
+    #[test]
+    fn report_mixed_order_r() {
+        use crate::hash::{HashTrait, PublicKeyEd25519};
+        use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+        use curve25519_dalek::scalar::Scalar;
+        use sha2::{Digest, Sha512};
+
+        let sk = ed25519_dalek::SigningKey::from_bytes(&[7; 32]);
+        let pk = sk.verifying_key();
+        let r0 = Scalar::from_bytes_mod_order([9; 32]);
+
+        // (0, -1), the point of order 2.
+        let mut t2 = [0xff; 32];
+        t2[0] = 0xec;
+        t2[31] = 0x7f;
+        let t2 = CompressedEdwardsY(t2).decompress().unwrap();
+        let r = (EdwardsPoint::mul_base(&r0) + t2).compress();
+
+        // With s = r0 + k * a, the batch equation leaves z * T2, which is the
+        // identity for even coefficients z. These are derived deterministically
+        // from the batch, so grind the message until one is.
+        let (msg, bytes) = (0u32..)
+            .find_map(|i| {
+                let msg = format!("message {i}").into_bytes();
+                let payload = crate::blake2b::digest_256(&msg);
+                let k = Sha512::new()
+                    .chain(r.as_bytes())
+                    .chain(pk.as_bytes())
+                    .chain(payload)
+                    .finalize();
+                let mut wide = [0; 64];
+                wide.copy_from_slice(&k);
+                let s = r0 + Scalar::from_bytes_mod_order_wide(&wide) * sk.to_scalar();
+
+                let mut bytes = [0; 64];
+                bytes[..32].copy_from_slice(r.as_bytes());
+                bytes[32..].copy_from_slice(s.as_bytes());
+                let signature = ed25519_dalek::Signature::from_bytes(&bytes);
+                ed25519_dalek::verify_batch(&[payload.as_slice()], &[signature], &[pk])
+                    .is_ok()
+                    .then_some((msg, bytes))
+            })
+            .unwrap();
+
+        let entry = (
+            PublicKey::Ed25519(PublicKeyEd25519::try_from_bytes(pk.as_bytes()).unwrap()),
+            Signature::try_from(bytes.to_vec()).unwrap(),
+            &msg[..],
+        );
+        assert!(!verify_one(&entry));
+
+        // Other entries would change the coefficients, so the batch is the one
+        // that ed25519_dalek accepts.
+        assert_eq!(
+            Err(BatchVerificationError { invalid: vec![0] }),
+            verify_batch(&[entry])
+        );
+    }
+}
@@ -16,18 +16,35 @@ pub enum Blake2bError {
 }
 
 /// Generate digest of length 256 bits (32bytes) from arbitrary binary data
-pub fn digest_256(data: &[u8]) -> Vec<u8> {
-    digest(data, 32).unwrap()
+pub fn digest_256(data: &[u8]) -> [u8; 32] {
+    digest_fixed([data])
 }
 
 // Generate digest of length 160 bits (20bytes) from arbitrary binary data
-pub fn digest_160(data: &[u8]) -> Vec<u8> {
-    digest(data, 20).unwrap()
+pub fn digest_160(data: &[u8]) -> [u8; 20] {
+    digest_fixed([data])
 }
 
 /// Generate digest of length 128 bits (16bytes) from arbitrary binary data
-pub fn digest_128(data: &[u8]) -> Vec<u8> {
-    digest(data, 16).unwrap()
+pub fn digest_128(data: &[u8]) -> [u8; 16] {
+    digest_fixed([data])
+}
+
+// Digest of `N` bytes of pieces of data. Only used with sizes between 16 and
+// 64 bytes, which Blake2b supports.
+pub(crate) fn digest_fixed<const N: usize, T, I>(data: T) -> [u8; N]
+where
+    T: IntoIterator<Item = I>,
+    I: AsRef<[u8]>,
+{
+    let mut hasher = Blake2b::new(N);
+    for d in data.into_iter() {
+        hasher.input(d.as_ref());
+    }
+
+    let mut result = [0; N];
+    hasher.result(&mut result);
+    result
 }
 
 /// Arbitrary Blake2b digest generation from generic data.
@@ -89,85 +106,108 @@ where
 //  1 2  3 4 5 5 5 5
 //
 //
-// TODO: optimize it,
-// this implementation will calculate the same hash [5, 5] two times.
-pub fn merkle_tree<Leaf>(list: &[Leaf]) -> Vec<u8>
+// Each level is computed from the one below it. Nodes past the end of a level
+// only cover padding, so they all have the same hash, computed once per level:
+// above, the hash of [5, 5] is computed once, and reused for [5, 5, 5, 5].
+pub fn merkle_tree<Leaf>(list: &[Leaf]) -> [u8; 32]
 where
     Leaf: AsRef<[u8]>,
 {
-    use std::ops::{Index, RangeFrom, RangeTo};
-
-    // Helper for calculating merkle tree
-    // The wrapper around slice which repeats last item forever
-    struct RepeatingSlice<'a, Leaf>(pub &'a [Leaf]);
+    if list.is_empty() {
+        digest_256(&[])
+    } else {
+        merkle_levels(list, 0, None)
+    }
+}
 
-    impl<'a, Leaf> Index<usize> for RepeatingSlice<'a, Leaf> {
-        type Output = Leaf;
+/// Proof that a leaf is the ith leaf of a tree computed by [merkle_tree].
+///
+/// As the tree is padded with the last leaf, the last leaf also has proofs at
+/// the indices of the padding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleProof {
+    /// Index of the leaf.
+    pub index: usize,
+    /// Hashes of the siblings of the nodes from the leaf to the root,
+    /// leaf first.
+    pub path: Vec<[u8; 32]>,
+}
 
-        fn index(&self, index: usize) -> &Self::Output {
-            if self.0.is_empty() {
-                panic!();
-            } else if index < self.0.len() {
-                self.0.index(index)
+impl MerkleProof {
+    /// Root of the tree with `leaf` at the index of the proof.
+    pub fn root(&self, leaf: impl AsRef<[u8]>) -> [u8; 32] {
+        let mut index = self.index;
+        let mut node = digest_256(leaf.as_ref());
+        for sibling in &self.path {
+            let pair = if index % 2 == 0 {
+                [&node, sibling]
             } else {
-                self.0.last().unwrap()
-            }
+                [sibling, &node]
+            };
+            node = digest_fixed(pair);
+            index /= 2;
         }
+        node
     }
 
-    impl<'a, Leaf> Index<RangeFrom<usize>> for RepeatingSlice<'a, Leaf> {
-        type Output = [Leaf];
+    /// Check that `leaf` is at the index of the proof, in the tree of root
+    /// `root`.
+    pub fn verify(&self, leaf: impl AsRef<[u8]>, root: impl AsRef<[u8]>) -> bool {
+        // the index must fit in the path, or its high bits would be ignored
+        let index_fits = u32::try_from(self.path.len())
+            .ok()
+            .and_then(|len| self.index.checked_shr(len))
+            .map_or(true, |high| high == 0);
+        index_fits && self.root(leaf)[..] == *root.as_ref()
+    }
+}
 
-        fn index(&self, index: RangeFrom<usize>) -> &Self::Output {
-            if self.0.is_empty() {
-                panic!();
-            } else if index.start < self.0.len() {
-                &self.0[index]
-            } else {
-                &self.0[(self.0.len() - 1)..]
-            }
-        }
+/// Proof that the ith element of `list` is in the tree computed by
+/// [merkle_tree], or `None` if `index` is out of bounds.
+pub fn merkle_proof<Leaf>(list: &[Leaf], index: usize) -> Option<MerkleProof>
+where
+    Leaf: AsRef<[u8]>,
+{
+    if index >= list.len() {
+        return None;
     }
 
-    impl<'a, Leaf> Index<RangeTo<usize>> for RepeatingSlice<'a, Leaf> {
-        type Output = [Leaf];
+    let mut path = Vec::new();
+    merkle_levels(list, index, Some(&mut path));
+    Some(MerkleProof { index, path })
+}
 
-        fn index(&self, index: RangeTo<usize>) -> &Self::Output {
-            if self.0.is_empty() {
-                panic!();
-            } else if index.end <= self.0.len() {
-                &self.0[index]
-            } else {
-                &self.0[..self.0.len()]
-            }
+// Root of the tree of the non-empty `list`, collecting the siblings of the
+// path of the leaf at `index` into `path`.
+fn merkle_levels<Leaf>(
+    list: &[Leaf],
+    mut index: usize,
+    mut path: Option<&mut Vec<[u8; 32]>>,
+) -> [u8; 32]
+where
+    Leaf: AsRef<[u8]>,
+{
+    let mut nodes = list
+        .iter()
+        .map(|leaf| digest_256(leaf.as_ref()))
+        .collect::<Vec<_>>();
+    // Hash of the subtrees of padding, at the current level.
+    let mut padding = nodes[nodes.len() - 1];
+
+    while nodes.len() > 1 {
+        if let Some(path) = path.as_deref_mut() {
+            path.push(*nodes.get(index ^ 1).unwrap_or(&padding));
         }
-    }
 
-    fn merkle_tree_inner<Leaf>(list: &RepeatingSlice<Leaf>, degree: u32) -> Vec<u8>
-    where
-        Leaf: AsRef<[u8]>,
-    {
-        match degree {
-            0 => digest_256(list[0].as_ref()),
-            d => {
-                let middle = 1 << (d - 1);
-                digest_all(
-                    &[
-                        merkle_tree_inner(&RepeatingSlice(&list[..middle]), d - 1),
-                        merkle_tree_inner(&RepeatingSlice(&list[middle..]), d - 1),
-                    ],
-                    32,
-                )
-                .unwrap() // we know length is within bounds, and that's the only error possible
-            }
-        }
+        nodes = nodes
+            .chunks(2)
+            .map(|pair| digest_fixed([&pair[0], pair.get(1).unwrap_or(&padding)]))
+            .collect();
+        padding = digest_fixed([padding, padding]);
+        index /= 2;
     }
 
-    if list.is_empty() {
-        digest_256(&[])
-    } else {
-        merkle_tree_inner(&RepeatingSlice(list), 64 - (list.len() - 1).leading_zeros())
-    }
+    nodes.swap_remove(0)
 }
 
 #[cfg(test)]
@@ -221,4 +261,65 @@ mod tests {
                 .unwrap()
         );
     }
+
+    // The recursive definition of the tree, padding with the last leaf.
+    fn merkle_tree_reference(list: &[Vec<u8>]) -> [u8; 32] {
+        fn inner(list: &[Vec<u8>], width: usize) -> [u8; 32] {
+            if width == 1 {
+                return digest_256(&list[0]);
+            }
+            let middle = width / 2;
+            let right = if list.len() > middle {
+                &list[middle..]
+            } else {
+                &list[list.len() - 1..]
+            };
+            let left = inner(&list[..middle.min(list.len())], middle);
+            digest_fixed([left, inner(right, middle)])
+        }
+
+        if list.is_empty() {
+            digest_256(&[])
+        } else {
+            inner(list, list.len().next_power_of_two())
+        }
+    }
+
+    #[test]
+    fn merkle_tree_and_proofs() {
+        assert_eq!(merkle_tree::<Vec<u8>>(&[]), digest_256(&[]));
+        assert!(merkle_proof::<Vec<u8>>(&[], 0).is_none());
+
+        for len in 1..=17u8 {
+            let list = (0..len).map(|i| vec![i; 32]).collect::<Vec<_>>();
+            let root = merkle_tree(&list);
+            assert_eq!(root, merkle_tree_reference(&list), "{} leaves", len);
+
+            for (index, leaf) in list.iter().enumerate() {
+                let proof = merkle_proof(&list, index).unwrap();
+                assert_eq!(
+                    proof.path.len(),
+                    list.len().next_power_of_two().trailing_zeros() as usize
+                );
+                assert!(proof.verify(leaf, root));
+                assert!(!proof.verify([len; 32], root));
+                // Siblings of the padding are copies of the last leaf.
+                let sibling = index ^ 1;
+                let moved = MerkleProof {
+                    index: sibling,
+                    path: proof.path.clone(),
+                };
+                assert_eq!(
+                    moved.verify(leaf, root),
+                    len > 1 && list[sibling.min(list.len() - 1)] == *leaf
+                );
+                let beyond = MerkleProof {
+                    index: index + list.len().next_power_of_two(),
+                    path: proof.path,
+                };
+                assert!(!beyond.verify(leaf, root));
+            }
+            assert!(merkle_proof(&list, list.len()).is_none());
+        }
+    }
 }
@@ -4,26 +4,35 @@
 // SPDX-License-Identifier: MIT
 
 //! BLS support (min_pk).
+//!
+//! Two backends are available: `blst`, with the `bls` feature, and a
+//! pure-Rust one based on `bls12_381`, with the `bls-pure` feature, which
+//! builds for `wasm32-unknown-unknown` without a C toolchain. Both give the
+//! same results. When both features are enabled, `blst` is used.
 
-#![cfg(feature = "bls")]
+#![cfg(any(feature = "bls", feature = "bls-pure"))]
 
+use crate::hash::BlsProofOfPossession;
 use crate::hash::BlsSignature;
 use crate::hash::ContractTz4Hash;
 use crate::hash::PublicKeyBls;
 use crate::hash::SecretKeyBls;
+use crate::watermark::Watermark;
 use crate::CryptoError;
 use crate::PublicKeyWithHash;
-use blst::min_pk;
-use blst::min_pk::{AggregateSignature, SecretKey};
-use blst::BLST_ERROR;
+use zeroize::Zeroize;
 
-impl TryFrom<&PublicKeyBls> for min_pk::PublicKey {
-    type Error = CryptoError;
+#[cfg(feature = "bls")]
+mod blst_backend;
+#[cfg(all(feature = "bls-pure", any(not(feature = "bls"), test)))]
+mod pure_backend;
 
-    fn try_from(source: &PublicKeyBls) -> Result<Self, Self::Error> {
-        min_pk::PublicKey::from_bytes(&source.0).map_err(|_| CryptoError::InvalidPublicKey)
-    }
-}
+mod threshold;
+
+#[cfg(feature = "bls")]
+use blst_backend as backend;
+#[cfg(not(feature = "bls"))]
+use pure_backend as backend;
 
 /// A message and a public key for this message.
 ///
@@ -31,25 +40,62 @@ impl TryFrom<&PublicKeyBls> for min_pk::PublicKey {
 /// Not currently being used, but the mode is available in the tezos protocol.
 pub type Message<'a> = (&'a [u8], &'a PublicKeyBls);
 
-/// Basic `dst` parameter for `blst`
+/// Basic `dst` parameter
 ///
 /// Same constant as used for `Basic` in `bls12-381` ocaml package used in tezos.
-#[allow(dead_code)]
 const BASIC_CIPHER_SUITE: &str = "BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
 
-/// Aug `dst` parameter for `blst`
+/// Aug `dst` parameter
 ///
 /// Same constant as used for `Basic` in `bls12-381` ocaml package used in tezos.
 /// This is the mode used for TORU.
 const AUG_CIPHER_SUITE: &str = "BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_AUG_";
 
-/// Pop signatures parameter value for `blst`
+/// Pop signatures parameter value
 ///
 /// Same constant as used for verify `Pop` in `bls12-381` ocaml package used in tezos.
-/// Not currently being used, but the mode is available in the tezos protocol.
-#[allow(dead_code)]
+/// Used for proofs of possession.
 const POP_CIPHER_SUITE: &str = "BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
 
+/// Pop `dst` parameter
+///
+/// Same constant as used for signatures of `Pop` in `bls12-381` ocaml package used in tezos.
+const POP_SIGNATURE_CIPHER_SUITE: &str = "BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// BLS signature scheme, as exposed by the `bls12-381` ocaml package.
+///
+/// The schemes differ in how they protect aggregate signatures against
+/// rogue key attacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlsScheme {
+    /// Messages of an aggregate signature must be distinct.
+    Basic,
+    /// Messages are prefixed by the public key. Used by tezos for tz4
+    /// signatures, and the default of this module.
+    Aug,
+    /// Public keys must be registered with a proof of possession, see
+    /// [SecretKeyBls::pop_prove].
+    Pop,
+}
+
+impl BlsScheme {
+    fn dst(self) -> &'static [u8] {
+        match self {
+            Self::Basic => BASIC_CIPHER_SUITE.as_bytes(),
+            Self::Aug => AUG_CIPHER_SUITE.as_bytes(),
+            Self::Pop => POP_SIGNATURE_CIPHER_SUITE.as_bytes(),
+        }
+    }
+
+    // Message actually signed by `pk`.
+    fn message(self, msg: &[u8], pk: &PublicKeyBls) -> Vec<u8> {
+        match self {
+            Self::Aug => prepend_public_key(msg, pk),
+            Self::Basic | Self::Pop => msg.to_vec(),
+        }
+    }
+}
+
 impl BlsSignature {
     /// Verify several messages with public keys and _one_ signature.
     ///
@@ -62,90 +108,223 @@ impl BlsSignature {
         &self,
         messages: &mut impl Iterator<Item = Message<'a>>,
     ) -> Result<bool, CryptoError> {
-        let signature: min_pk::Signature = self.try_into()?;
-
-        let messages_with_pk = messages
-            .map(|(message, public_key)| {
-                // For saftey, we ensure that each message is unique by prepending the
-                // public key.
-                let message_with_pk = prepend_public_key(message, public_key);
-
-                public_key
-                    .try_into()
-                    .map(move |public_key| (message_with_pk, public_key))
-            })
-            .collect::<Result<Vec<(Vec<u8>, min_pk::PublicKey)>, _>>()?;
-
-        let messages = messages_with_pk
-            .iter()
-            .map(|(message, _)| message.as_slice())
-            .collect::<Vec<_>>();
+        self.aggregate_verify_with_scheme(BlsScheme::Aug, messages)
+    }
 
-        let public_keys = messages_with_pk
-            .iter()
-            .map(|(_, pk)| pk)
-            .collect::<Vec<_>>();
+    /// Verify several messages with public keys and _one_ signature, in the
+    /// given scheme.
+    ///
+    /// In the [BlsScheme::Basic] scheme, verification fails if messages are
+    /// not distinct.
+    pub fn aggregate_verify_with_scheme<'a>(
+        &self,
+        scheme: BlsScheme,
+        messages: &mut impl Iterator<Item = Message<'a>>,
+    ) -> Result<bool, CryptoError> {
+        // For saftey, in the Aug scheme, we ensure that each message is
+        // unique by prepending the public key.
+        let (messages, public_keys): (Vec<_>, Vec<&PublicKeyBls>) = messages
+            .map(|(message, public_key)| (scheme.message(message, public_key), public_key))
+            .unzip();
+
+        if scheme == BlsScheme::Basic {
+            let distinct = messages.iter().collect::<std::collections::BTreeSet<_>>();
+            if distinct.len() != messages.len() {
+                return Ok(false);
+            }
+        }
 
-        // Tezos_crypto uses the Aug suite
-        let dst = AUG_CIPHER_SUITE.as_bytes();
+        let messages = messages.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        let public_keys = public_keys.iter().map(|pk| &pk.0[..]).collect::<Vec<_>>();
 
-        match signature.aggregate_verify(true, &messages, dst, &public_keys, true) {
-            BLST_ERROR::BLST_SUCCESS => Ok(true),
-            BLST_ERROR::BLST_VERIFY_FAIL => Ok(false),
-            err => Err(CryptoError::AlgorithmError(format!("BLS_ERROR: {:?}", err))),
-        }
+        backend::aggregate_verify(&self.0, &messages, &public_keys, scheme.dst())
     }
 
     /// Aggregate individual signatures into a single signature.
     pub fn aggregate_sigs(sigs: &[&Self]) -> Result<Self, CryptoError> {
-        let sigs = sigs
-            .iter()
-            .map(|s| min_pk::Signature::try_from(*s))
-            .collect::<Result<Vec<_>, _>>()?;
+        let sigs = sigs.iter().map(|sig| sig.0.as_slice()).collect::<Vec<_>>();
 
-        let sigs = sigs.iter().collect::<Vec<_>>();
+        backend::aggregate_signatures(&sigs).map(Self)
+    }
 
-        let aggregate = AggregateSignature::aggregate(sigs.as_slice(), true)
-            .map_err(|e| CryptoError::AlgorithmError(format!("BLST_ERROR: {:?}", e)))?;
+    /// Verify _one_ signature of the same message by several public keys, in
+    /// the [BlsScheme::Pop] scheme.
+    ///
+    /// The signature is the aggregate of the signatures of each public key,
+    /// see [BlsSignature::aggregate_sigs]. The public keys must have been
+    /// registered with a proof of possession, see [PublicKeyBls::pop_verify].
+    /// Verification fails if there are no public keys.
+    pub fn fast_aggregate_verify(
+        &self,
+        message: &[u8],
+        public_keys: &[&PublicKeyBls],
+    ) -> Result<bool, CryptoError> {
+        if public_keys.is_empty() {
+            return Ok(false);
+        }
 
-        aggregate
-            .validate()
-            .map_err(|e| CryptoError::AlgorithmError(format!("BLST_ERROR: {:?}", e)))?;
+        PublicKeyBls::aggregate(public_keys)?.verify_with_scheme(BlsScheme::Pop, self, message)
+    }
 
-        Ok(Self(aggregate.to_signature().compress().to_vec()))
+    /// Verify an aggregate signature of a consensus operation, such as an
+    /// attestation, by every member of `committee`.
+    ///
+    /// `content` is the operation as signed by each member, without its
+    /// watermark.
+    pub fn verify_committee(
+        &self,
+        watermark: &Watermark,
+        content: &[u8],
+        committee: &[&PublicKeyBls],
+    ) -> Result<bool, CryptoError> {
+        self.fast_aggregate_verify(&watermark.apply(content), committee)
     }
 }
 
-impl TryFrom<&BlsSignature> for min_pk::Signature {
-    type Error = CryptoError;
-
-    fn try_from(value: &BlsSignature) -> Result<Self, Self::Error> {
-        min_pk::Signature::from_bytes(&value.0).map_err(|_| CryptoError::InvalidSignature)
+/// Verify a batch of signatures, each of its own message, with a single
+/// multi-pairing.
+///
+/// Returns `Ok(false)` if any signature is invalid, without telling which.
+pub fn verify_batch(batch: &[(&PublicKeyBls, &BlsSignature, &[u8])]) -> Result<bool, CryptoError> {
+    if batch.is_empty() {
+        return Ok(true);
     }
+
+    let public_keys = batch
+        .iter()
+        .map(|(pk, _, _)| pk.0.as_slice())
+        .collect::<Vec<_>>();
+    let signatures = batch
+        .iter()
+        .map(|(_, sig, _)| sig.0.as_slice())
+        .collect::<Vec<_>>();
+    let messages = batch
+        .iter()
+        .map(|(pk, _, msg)| prepend_public_key(msg, pk))
+        .collect::<Vec<_>>();
+
+    // The random scalars are derived from the whole batch, so that they
+    // cannot be known by signers in advance.
+    let transcript = crate::blake2b::digest_all(
+        batch
+            .iter()
+            .flat_map(|(pk, sig, msg)| [pk.0.as_slice(), sig.0.as_slice(), msg]),
+        32,
+    )
+    .map_err(|e| CryptoError::AlgorithmError(format!("{:?}", e)))?;
+    let rands = (0..batch.len() as u64)
+        .map(|i| {
+            let digest = crate::blake2b::digest_256(&[&transcript[..], &i.to_le_bytes()].concat());
+            let mut rand = [0; 8];
+            rand.copy_from_slice(&digest[..8]);
+            // Scalars must be non-zero.
+            u64::from_le_bytes(rand) | 1
+        })
+        .collect::<Vec<_>>();
+
+    let messages = messages.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+    backend::verify_multiple(
+        &messages,
+        &public_keys,
+        &signatures,
+        &rands,
+        AUG_CIPHER_SUITE.as_bytes(),
+    )
 }
 
 /// Bls SecretKey
 impl SecretKeyBls {
     /// Derive the public key for the current secret key.
     pub fn derive_pk(&self) -> Result<PublicKeyBls, CryptoError> {
-        let sk = SecretKey::from_bytes(&self.0)
-            .map_err(|e| CryptoError::AlgorithmError(format!("BLST_ERROR: {:?}", e)))?;
-
-        let pk = sk.sk_to_pk();
+        backend::sk_to_pk(&self.0).map(PublicKeyBls)
+    }
 
-        Ok(PublicKeyBls(pk.to_bytes().to_vec()))
+    /// Generate a random secret key.
+    pub fn generate<R>(rng: &mut R) -> Result<Self, CryptoError>
+    where
+        R: rand::RngCore + rand::CryptoRng,
+    {
+        let mut ikm = [0; 32];
+        rng.fill_bytes(&mut ikm);
+        let keypair = keypair_from_ikm(ikm);
+        ikm.zeroize();
+        keypair.map(|(sk, _)| sk)
     }
 
     /// Sign the given data.
+    ///
+    /// Like Tezos_crypto, signing uses the Aug suite.
     pub fn sign(&self, message: impl AsRef<[u8]>) -> Result<BlsSignature, CryptoError> {
-        let sk = SecretKey::from_bytes(&self.0)
-            .map_err(|e| CryptoError::AlgorithmError(format!("BLST_ERROR: {:?}", e)))?;
+        self.sign_with_scheme(BlsScheme::Aug, message)
+    }
 
-        let pk = sk.sk_to_pk();
-        let msg = prepend_public_key(message.as_ref(), &PublicKeyBls(pk.to_bytes().to_vec()));
-        let sig = sk.sign(&msg, AUG_CIPHER_SUITE.as_bytes(), &[]);
+    /// Sign the given data in the given scheme.
+    pub fn sign_with_scheme(
+        &self,
+        scheme: BlsScheme,
+        message: impl AsRef<[u8]>,
+    ) -> Result<BlsSignature, CryptoError> {
+        let pk = self.derive_pk()?;
+        let msg = scheme.message(message.as_ref(), &pk);
 
-        Ok(BlsSignature(sig.to_bytes().to_vec()))
+        backend::sign(&self.0, &msg, scheme.dst()).map(BlsSignature)
+    }
+
+    /// Sign `message` prefixed by the given watermark.
+    pub fn sign_with_watermark(
+        &self,
+        watermark: &Watermark,
+        message: impl AsRef<[u8]>,
+    ) -> Result<BlsSignature, CryptoError> {
+        self.sign(watermark.apply(message.as_ref()))
+    }
+
+    /// Prove possession of the secret key, by signing its public key.
+    ///
+    /// Same as `Bls.pop_prove` in octez, as required to register a tz4
+    /// consensus key.
+    pub fn pop_prove(&self) -> Result<BlsProofOfPossession, CryptoError> {
+        let pk = self.derive_pk()?;
+
+        backend::sign(&self.0, &pk.0, POP_CIPHER_SUITE.as_bytes()).map(BlsProofOfPossession)
+    }
+}
+
+impl PublicKeyBls {
+    /// Aggregate public keys into a single public key, which verifies the
+    /// aggregate of their signatures of the same message, see
+    /// [BlsSignature::fast_aggregate_verify].
+    pub fn aggregate(public_keys: &[&Self]) -> Result<Self, CryptoError> {
+        let public_keys = public_keys.iter().map(|pk| &pk.0[..]).collect::<Vec<_>>();
+
+        backend::aggregate_public_keys(&public_keys).map(Self)
+    }
+
+    /// Verify a signature of `message` in the given scheme.
+    ///
+    /// [PublicKeySignatureVerifier::verify_signature] uses the Aug scheme.
+    ///
+    /// [PublicKeySignatureVerifier::verify_signature]: crate::PublicKeySignatureVerifier::verify_signature
+    pub fn verify_with_scheme(
+        &self,
+        scheme: BlsScheme,
+        signature: &BlsSignature,
+        message: &[u8],
+    ) -> Result<bool, CryptoError> {
+        signature.aggregate_verify_with_scheme(scheme, &mut [(message, self)].into_iter())
+    }
+
+    /// Verify a proof of possession of the secret key of this public key.
+    ///
+    /// Same as `Bls.pop_verify` in octez.
+    pub fn pop_verify(&self, proof: &BlsProofOfPossession) -> Result<bool, CryptoError> {
+        backend::aggregate_verify(
+            &proof.0,
+            &[&self.0],
+            &[&self.0],
+            POP_CIPHER_SUITE.as_bytes(),
+        )
     }
 }
 
@@ -160,13 +339,8 @@ fn prepend_public_key(msg: &[u8], pk: &PublicKeyBls) -> Vec<u8> {
 
 /// Generate a keypair from initial key material.
 pub fn keypair_from_ikm(ikm: [u8; 32]) -> Result<(SecretKeyBls, PublicKeyBls), CryptoError> {
-    let sk = SecretKey::key_gen(&ikm, &[])
-        .map_err(|e| CryptoError::AlgorithmError(format!("BLST_ERROR: {:?}", e)))?;
-
-    let pk = sk.sk_to_pk();
-
-    let pk = PublicKeyBls(pk.to_bytes().to_vec());
-    let sk = SecretKeyBls(sk.to_bytes().to_vec());
+    let sk = SecretKeyBls(backend::key_gen(&ikm)?);
+    let pk = sk.derive_pk()?;
 
     Ok((sk, pk))
 }
@@ -238,84 +412,16 @@ mod tests {
     };
     use proptest::prelude::*;
 
-    #[test]
-    fn decoding_invalid_signature_gives_error() {
-        use super::CryptoError;
-
-        let bytes: [u8; 96] = [
-            212, 164, 10, 104, 205, 205, 23, 255, 218, 184, 156, 159, 150, 133, 185, 31, 221, 34,
-            11, 39, 189, 17, 16, 28, 109, 72, 109, 48, 239, 105, 121, 121, 100, 149, 1, 168, 106,
-            118, 145, 148, 182, 122, 206, 83, 8, 214, 146, 238, 181, 41, 182, 23, 221, 66, 47, 99,
-            179, 9, 195, 96, 141, 204, 99, 53, 222, 157, 64, 102, 177, 118, 26, 240, 235, 189, 109,
-            214, 229, 77, 77, 24, 53, 136, 220, 124, 102, 108, 5, 241, 185, 98, 145, 206, 121, 169,
-            11, 255,
-        ];
-
-        let sig = BlsSignature(bytes.to_vec());
-        let sig = min_pk::Signature::try_from(&sig);
-
-        assert!(matches!(sig, Err(CryptoError::InvalidSignature)));
-    }
-
-    #[test]
-    fn decoding_valid_signature_is_ok() {
-        let bytes: [u8; 96] = [
-            149, 240, 234, 160, 166, 30, 18, 15, 229, 113, 68, 192, 204, 118, 169, 78, 252, 237,
-            251, 111, 240, 127, 236, 68, 231, 114, 243, 76, 61, 156, 148, 34, 203, 153, 6, 255,
-            159, 108, 21, 71, 163, 120, 87, 133, 239, 135, 225, 127, 14, 126, 215, 20, 107, 206,
-            222, 198, 187, 11, 173, 56, 167, 119, 182, 55, 57, 102, 180, 194, 18, 91, 49, 59, 130,
-            39, 33, 103, 243, 211, 156, 164, 53, 160, 255, 198, 58, 2, 124, 121, 201, 44, 139, 167,
-            48, 52, 211, 178,
-        ];
-
-        let sig = BlsSignature(bytes.to_vec());
-        let sig = min_pk::Signature::try_from(&sig);
-
-        assert!(sig.is_ok());
-    }
-
-    #[test]
-    fn return_error_on_invalid_public_key_encoding() {
-        use super::{min_pk, PublicKeyBls};
-
-        let bytes: [u8; 48] = [
-            118, 187, 155, 125, 42, 190, 144, 143, 145, 250, 125, 184, 90, 9, 210, 24, 202, 72, 22,
-            137, 121, 174, 233, 107, 175, 63, 167, 107, 192, 38, 60, 102, 74, 213, 169, 88, 51,
-            181, 190, 79, 226, 209, 166, 137, 54, 88, 14, 28,
-        ];
-
-        let pk = PublicKeyBls(bytes.to_vec());
-        let pk = min_pk::PublicKey::try_from(&pk);
-
-        assert!(matches!(pk, Err(CryptoError::InvalidPublicKey)));
-    }
-
-    #[test]
-    fn decode_valid_public_key_is_ok() {
-        let bytes: [u8; 48] = [
-            145, 150, 3, 232, 142, 40, 236, 191, 116, 53, 15, 47, 240, 143, 182, 94, 110, 121, 160,
-            108, 247, 42, 34, 231, 133, 156, 81, 111, 62, 109, 59, 223, 198, 220, 89, 7, 173, 251,
-            241, 82, 161, 86, 161, 40, 141, 57, 145, 123,
-        ];
-
-        let pk = PublicKeyBls(bytes.to_vec());
-        let pk = min_pk::PublicKey::try_from(&pk);
-
-        assert!(pk.is_ok());
-    }
-
     #[test]
     fn can_verify_signature_is_true() {
         use super::AUG_CIPHER_SUITE;
-        use blst::min_pk::SecretKey;
 
         let ikm: [u8; 32] = [
             206, 83, 215, 142, 19, 242, 183, 160, 92, 186, 87, 192, 89, 109, 82, 0, 17, 60, 248,
             194, 149, 144, 24, 238, 202, 18, 75, 107, 139, 241, 104, 198,
         ];
 
-        let sk = SecretKey::key_gen(&ikm, &[]).unwrap();
-        let pk = PublicKeyBls(sk.sk_to_pk().to_bytes().to_vec());
+        let (sk, pk) = keypair_from_ikm(ikm).unwrap();
 
         let dst = AUG_CIPHER_SUITE.as_bytes();
 
@@ -324,7 +430,7 @@ mod tests {
         signed_bytes.extend_from_slice(&pk.0);
         signed_bytes.extend_from_slice(msg);
 
-        let sig = BlsSignature(sk.sign(&signed_bytes, dst, &[]).to_bytes().to_vec());
+        let sig = BlsSignature(backend::sign(&sk.0, &signed_bytes, dst).unwrap());
 
         let msg_keys = [(&msg[..], &pk)];
         let res = sig.aggregate_verify(&mut msg_keys.into_iter());
@@ -334,6 +440,7 @@ mod tests {
 
     // Values taken from tezt test, that was failing due to public key not being
     // prepended to msg.
+    #[cfg(not(feature = "mavryk"))]
     #[test]
     fn bls_sign_with_public_key() {
         let ikm = [0; 32];
@@ -381,12 +488,20 @@ mod tests {
             sig.0.as_slice(),
             "expected signatures to match"
         );
+
+        // Signatures of octez are in the Aug scheme.
+        let aug = sk.sign_with_scheme(BlsScheme::Aug, msg_bytes).unwrap();
+        assert_eq!(expected_sig, aug.0.as_slice());
+        assert!(pk
+            .verify_with_scheme(BlsScheme::Aug, &aug, msg_bytes)
+            .unwrap());
+        assert!(!pk
+            .verify_with_scheme(BlsScheme::Basic, &aug, msg_bytes)
+            .unwrap());
     }
 
     #[test]
     fn can_verify_signature_is_false() {
-        use blst::min_pk::SecretKey;
-
         let ikm: [u8; 32] = [
             139, 238, 61, 128, 196, 109, 58, 44, 13, 240, 207, 148, 246, 216, 242, 161, 132, 197,
             169, 201, 120, 146, 252, 112, 92, 255, 57, 102, 202, 178, 210, 113,
@@ -401,11 +516,10 @@ mod tests {
             48, 52, 211, 178,
         ];
 
-        let sk = SecretKey::key_gen(&ikm, &[]).unwrap();
-        let pk = PublicKeyBls(sk.sk_to_pk().to_bytes().to_vec());
+        let (_, pk) = keypair_from_ikm(ikm).unwrap();
 
         let msg = b"blst is such a blast";
-        let sig = BlsSignature(signature_bytes.to_vec());
+        let sig = BlsSignature(signature_bytes);
 
         let msg_keys = [(&msg[..], &pk)];
         let res = sig.aggregate_verify(&mut msg_keys.into_iter());
@@ -413,6 +527,197 @@ mod tests {
         assert!(matches!(res, Ok(false)));
     }
 
+    // Pop signatures are the same as those of Ethereum consensus, whose
+    // `sign` test vectors share the ciphersuite.
+    #[test]
+    fn sign_pop_scheme_vectors() {
+        let sk = SecretKeyBls(
+            hex::decode("263dbd792f5b1be47ed85f8938c0f29586af0d3ac7b977f21c278fe1462040e3")
+                .unwrap(),
+        );
+        let pk = sk.derive_pk().unwrap();
+
+        let test_cases = [
+            (
+                [0x00; 32],
+                "b6ed936746e01f8ecf281f020953fbf1f01debd5657c4a383940b020b26507f6076334f91e2366c96e9ab279fb5158090352ea1c5b0c9274504f4f0e7053af24802e51e4568d164fe986834f41e55c8e850ce1f98458c0cfc9ab380b55285a55",
+            ),
+            (
+                [0x56; 32],
+                "882730e5d03f6b42c3abc26d3372625034e1d871b65a8a6b900a56dae22da98abbe1b68f85e49fe7652a55ec3d0591c20767677e33e5cbb1207315c41a9ac03be39c2e7668edc043d6cb1d9fd93033caa8a1c5b0e84bedaeb6c64972503a43eb",
+            ),
+        ];
+
+        for (msg, expected) in test_cases {
+            let sig = sk.sign_with_scheme(BlsScheme::Pop, msg).unwrap();
+            assert_eq!(expected, hex::encode(sig.0));
+            assert!(pk.verify_with_scheme(BlsScheme::Pop, &sig, &msg).unwrap());
+        }
+    }
+
+    // Basic signatures are the same as those of Filecoin, whose test vectors
+    // share the ciphersuite.
+    #[test]
+    fn sign_basic_scheme_vectors() {
+        let test_cases = [
+            (
+                "3ce2e976962a07ab68ccfa29194968dbb6c917c041d44bfc1c9f1a671017f70e",
+                "b2be11dc8e54ee74dbc07569fd74fe03b5f52ad71cd49a8579b6c6387891f5a20ad980ec2747618c1b9ad35846a68a3e",
+                "",
+                "b53cfdf8b488a286df1ed20432e2bbc4e6361003757dfda3a4fd6cd98de95e5513f7c448d70b2681e14547a6ced47e7c10e28432e8abcb34de1dc28f39328fd2a13db12a4c6a30bd17b0e42881a429003e4c24583ba0f29a40fd836cf05e1a40",
+            ),
+            (
+                "6de2989580e8210501e005a7e45f645fc525518d4d2acf1b7fce5852d5d3fe5f",
+                "981de2d88a80a2d7752ecda66443340a789ea62dd68dca6a3a8caf3b6c1e94248a8819a4f6ba554f50f5ccb8bc40e67c",
+                "1234",
+                "84aa59cad078a34c3c1f876e924ee199cd8cf74857cebcad3037561964cfda50dce5f4d0709aa690dae7113b01a9c8c31557f5589c38eb720e86864ff0c4446fba21899d4cd0b2862ec395de1dfdb736bf38ca56d17019b257c5d4dd563bf5b7",
+            ),
+            (
+                "16696c2bb32968007ca92ec631286349ec19eddb461605c7eaa1d88e08dd356d",
+                "a31e3adf1fd542e00c8ae3d4dafea1133c09cd71723584b159cfb78ab5e1bad97f955b891f761c79fa8215b8c53addf7",
+                "4fb3300556a1b2e3978f5da60cb0708b4d41385a6008976a88527d418d21cf9e",
+                "8d4512fabe80f7fb68726794a86b2e20cd04b5787aecfbf43b02c8eb67e42b60b797eba0489c4eb1165e4391f31ee8600af43a3d57e6bfdbb950564916f6b1c57e4ef7fba312fd7db431aaec906fa38acefe90687d3755cb3d10b19901bd0074",
+            ),
+        ];
+
+        for (sk, expected_pk, msg, expected) in test_cases {
+            let sk = SecretKeyBls(hex::decode(sk).unwrap());
+            let pk = sk.derive_pk().unwrap();
+            assert_eq!(expected_pk, hex::encode(pk.0));
+            let sig = sk.sign_with_scheme(BlsScheme::Basic, msg).unwrap();
+            assert_eq!(expected, hex::encode(sig.0));
+            assert!(pk
+                .verify_with_scheme(BlsScheme::Basic, &sig, msg.as_bytes())
+                .unwrap());
+        }
+    }
+
+    #[test]
+    fn schemes_are_distinct() {
+        let (sk, pk) = keypair_from_ikm([1; 32]).unwrap();
+        let msg = b"message";
+
+        assert_eq!(
+            sk.sign(msg).unwrap(),
+            sk.sign_with_scheme(BlsScheme::Aug, msg).unwrap()
+        );
+
+        let schemes = [BlsScheme::Basic, BlsScheme::Aug, BlsScheme::Pop];
+        for signing in schemes {
+            let sig = sk.sign_with_scheme(signing, msg).unwrap();
+            for verifying in schemes {
+                assert_eq!(
+                    signing == verifying,
+                    pk.verify_with_scheme(verifying, &sig, msg).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn aggregate_verify_basic_requires_distinct_messages() {
+        let (sk1, pk1) = keypair_from_ikm([1; 32]).unwrap();
+        let (sk2, pk2) = keypair_from_ikm([2; 32]).unwrap();
+
+        for scheme in [BlsScheme::Basic, BlsScheme::Aug, BlsScheme::Pop] {
+            let sig1 = sk1.sign_with_scheme(scheme, b"message").unwrap();
+            let sig2 = sk2.sign_with_scheme(scheme, b"message").unwrap();
+            let sig = BlsSignature::aggregate_sigs(&[&sig1, &sig2]).unwrap();
+
+            let messages = [(&b"message"[..], &pk1), (&b"message"[..], &pk2)];
+            let valid = sig
+                .aggregate_verify_with_scheme(scheme, &mut messages.into_iter())
+                .unwrap();
+            assert_eq!(scheme != BlsScheme::Basic, valid);
+
+            let sig2 = sk2.sign_with_scheme(scheme, b"other message").unwrap();
+            let sig = BlsSignature::aggregate_sigs(&[&sig1, &sig2]).unwrap();
+
+            let messages = [(&b"message"[..], &pk1), (&b"other message"[..], &pk2)];
+            assert!(sig
+                .aggregate_verify_with_scheme(scheme, &mut messages.into_iter())
+                .unwrap());
+        }
+    }
+
+    #[test]
+    fn fast_aggregate_verify() {
+        use crate::hash::{ChainId, HashTrait};
+
+        let keypairs = (0..4u8)
+            .map(|i| keypair_from_ikm([i; 32]).unwrap())
+            .collect::<Vec<_>>();
+        let committee = keypairs.iter().map(|(_, pk)| pk).collect::<Vec<_>>();
+
+        let chain_id = ChainId::from_b58check("NetXdQprcVkpaWU").unwrap();
+        let watermark = Watermark::Attestation(chain_id);
+        let content = [0x15; 40];
+
+        let sigs = keypairs
+            .iter()
+            .map(|(sk, _)| {
+                sk.sign_with_scheme(BlsScheme::Pop, watermark.apply(&content))
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let sig = BlsSignature::aggregate_sigs(&sigs.iter().collect::<Vec<_>>()).unwrap();
+
+        assert!(sig
+            .verify_committee(&watermark, &content, &committee)
+            .unwrap());
+        assert!(sig
+            .fast_aggregate_verify(&watermark.apply(&content), &committee)
+            .unwrap());
+
+        // The aggregate public key verifies the aggregate signature.
+        let aggregate = PublicKeyBls::aggregate(&committee).unwrap();
+        assert!(aggregate
+            .verify_with_scheme(BlsScheme::Pop, &sig, &watermark.apply(&content))
+            .unwrap());
+
+        // Missing member, wrong content and empty committee.
+        assert!(!sig
+            .verify_committee(&watermark, &content, &committee[1..])
+            .unwrap());
+        assert!(!sig
+            .verify_committee(&watermark, &[0x16; 40], &committee)
+            .unwrap());
+        assert!(!sig.verify_committee(&watermark, &content, &[]).unwrap());
+        assert!(PublicKeyBls::aggregate(&[]).is_err());
+    }
+
+    #[test]
+    fn proof_of_possession() {
+        use tezos_data_encoding::enc::BinWriter;
+        use tezos_data_encoding::nom::NomReader;
+
+        let (sk, pk) = keypair_from_ikm([7; 32]).unwrap();
+        let (_, other_pk) = keypair_from_ikm([8; 32]).unwrap();
+
+        let proof = sk.pop_prove().unwrap();
+        assert!(pk.pop_verify(&proof).unwrap());
+        assert!(!other_pk.pop_verify(&proof).unwrap());
+
+        // A signature of the public key is not a proof of possession.
+        let sig = sk.sign(pk.0).unwrap();
+        let not_proof = BlsProofOfPossession(sig.0);
+        assert!(!pk.pop_verify(&not_proof).unwrap());
+
+        let encoded = proof.to_b58check();
+        assert!(encoded.starts_with("BLsig"));
+        assert_eq!(
+            proof,
+            BlsProofOfPossession::from_b58check(&encoded).unwrap()
+        );
+
+        let mut bytes = Vec::new();
+        proof.bin_write(&mut bytes).unwrap();
+        assert_eq!(96, bytes.len());
+        let (rest, decoded) = BlsProofOfPossession::nom_read(&bytes).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(proof, decoded);
+    }
+
     // Test to ensure that we use the correct hashing scheme to convert between
     // bls::PublicKey and ContractTz4Hash.
     //
@@ -443,7 +748,7 @@ mod tests {
             let pk_bytes: [u8; 48] = pk_bytes[4..] // remove prefix of `BLpk`
                 .try_into()
                 .expect("pk_bytes should be 48 bytes long");
-            let pk = PublicKeyBls(pk_bytes.to_vec());
+            let pk = PublicKeyBls(pk_bytes);
 
             let tz4 = pk.pk_hash();
 
@@ -0,0 +1,249 @@
+// SPDX-FileCopyrightText: 2024 Trilitech <contact@trili.tech>
+//
+// SPDX-License-Identifier: MIT
+
+//! BLS backend based on `blst`.
+
+use blst::min_pk;
+use blst::min_pk::{AggregatePublicKey, AggregateSignature, SecretKey};
+use blst::BLST_ERROR;
+
+use crate::hash::{BlsSignature, PublicKeyBls};
+use crate::CryptoError;
+
+impl TryFrom<&PublicKeyBls> for min_pk::PublicKey {
+    type Error = CryptoError;
+
+    fn try_from(source: &PublicKeyBls) -> Result<Self, Self::Error> {
+        min_pk::PublicKey::from_bytes(&source.0).map_err(|_| CryptoError::InvalidPublicKey)
+    }
+}
+
+impl TryFrom<&BlsSignature> for min_pk::Signature {
+    type Error = CryptoError;
+
+    fn try_from(value: &BlsSignature) -> Result<Self, Self::Error> {
+        min_pk::Signature::from_bytes(&value.0).map_err(|_| CryptoError::InvalidSignature)
+    }
+}
+
+fn blst_error(e: BLST_ERROR) -> CryptoError {
+    CryptoError::AlgorithmError(format!("BLST_ERROR: {:?}", e))
+}
+
+fn secret_key(sk: &[u8]) -> Result<SecretKey, CryptoError> {
+    SecretKey::from_bytes(sk).map_err(blst_error)
+}
+
+fn public_key(pk: &[u8]) -> Result<min_pk::PublicKey, CryptoError> {
+    min_pk::PublicKey::from_bytes(pk).map_err(|_| CryptoError::InvalidPublicKey)
+}
+
+fn signature(sig: &[u8]) -> Result<min_pk::Signature, CryptoError> {
+    min_pk::Signature::from_bytes(sig).map_err(|_| CryptoError::InvalidSignature)
+}
+
+fn verify_result(result: BLST_ERROR) -> Result<bool, CryptoError> {
+    match result {
+        BLST_ERROR::BLST_SUCCESS => Ok(true),
+        BLST_ERROR::BLST_VERIFY_FAIL => Ok(false),
+        err => Err(CryptoError::AlgorithmError(format!("BLS_ERROR: {:?}", err))),
+    }
+}
+
+pub(super) fn key_gen(ikm: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let sk = SecretKey::key_gen(ikm, &[]).map_err(blst_error)?;
+    Ok(sk.to_bytes().to_vec())
+}
+
+pub(super) fn sk_to_pk(sk: &[u8]) -> Result<[u8; 48], CryptoError> {
+    Ok(secret_key(sk)?.sk_to_pk().to_bytes())
+}
+
+pub(super) fn sign(sk: &[u8], msg: &[u8], dst: &[u8]) -> Result<[u8; 96], CryptoError> {
+    Ok(secret_key(sk)?.sign(msg, dst, &[]).to_bytes())
+}
+
+pub(super) fn aggregate_verify(
+    sig: &[u8],
+    msgs: &[&[u8]],
+    pks: &[&[u8]],
+    dst: &[u8],
+) -> Result<bool, CryptoError> {
+    let signature = signature(sig)?;
+    let public_keys = pks
+        .iter()
+        .map(|pk| public_key(pk))
+        .collect::<Result<Vec<_>, _>>()?;
+    let public_keys = public_keys.iter().collect::<Vec<_>>();
+
+    verify_result(signature.aggregate_verify(true, msgs, dst, &public_keys, true))
+}
+
+pub(super) fn verify_multiple(
+    msgs: &[&[u8]],
+    pks: &[&[u8]],
+    sigs: &[&[u8]],
+    rands: &[u64],
+    dst: &[u8],
+) -> Result<bool, CryptoError> {
+    let public_keys = pks
+        .iter()
+        .map(|pk| public_key(pk))
+        .collect::<Result<Vec<_>, _>>()?;
+    let signatures = sigs
+        .iter()
+        .map(|sig| signature(sig))
+        .collect::<Result<Vec<_>, _>>()?;
+    let rands = rands
+        .iter()
+        .map(|rand| {
+            let mut scalar = blst::blst_scalar::default();
+            scalar.b[..8].copy_from_slice(&rand.to_le_bytes());
+            scalar
+        })
+        .collect::<Vec<_>>();
+
+    let public_keys = public_keys.iter().collect::<Vec<_>>();
+    let signatures = signatures.iter().collect::<Vec<_>>();
+
+    verify_result(min_pk::Signature::verify_multiple_aggregate_signatures(
+        msgs,
+        dst,
+        &public_keys,
+        true,
+        &signatures,
+        true,
+        &rands,
+        64,
+    ))
+}
+
+pub(super) fn aggregate_signatures(sigs: &[&[u8]]) -> Result<[u8; 96], CryptoError> {
+    let sigs = sigs
+        .iter()
+        .map(|sig| signature(sig))
+        .collect::<Result<Vec<_>, _>>()?;
+    let sigs = sigs.iter().collect::<Vec<_>>();
+
+    let aggregate = AggregateSignature::aggregate(sigs.as_slice(), true).map_err(blst_error)?;
+    aggregate.validate().map_err(blst_error)?;
+
+    Ok(aggregate.to_signature().compress())
+}
+
+pub(super) fn aggregate_public_keys(pks: &[&[u8]]) -> Result<[u8; 48], CryptoError> {
+    let pks = pks
+        .iter()
+        .map(|pk| public_key(pk))
+        .collect::<Result<Vec<_>, _>>()?;
+    let pks = pks.iter().collect::<Vec<_>>();
+
+    let aggregate = AggregatePublicKey::aggregate(pks.as_slice(), true).map_err(blst_error)?;
+
+    Ok(aggregate.to_public_key().compress())
+}
+
+// Compressed point at infinity.
+const IDENTITY: [u8; 96] = {
+    let mut bytes = [0; 96];
+    bytes[0] = 0xc0;
+    bytes
+};
+
+// Double-and-add, with the big-endian `scalar`.
+fn mul(point: &AggregateSignature, scalar: &[u8; 32]) -> Result<AggregateSignature, CryptoError> {
+    let mut result = AggregateSignature::from_signature(&signature(&IDENTITY)?);
+    for byte in scalar {
+        for bit in (0..8).rev() {
+            let double = result;
+            result.add_aggregate(&double);
+            if (byte >> bit) & 1 == 1 {
+                result.add_aggregate(point);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Sum of the signatures multiplied by the big-endian `scalars`.
+pub(super) fn linear_combination(
+    sigs: &[&[u8]],
+    scalars: &[[u8; 32]],
+) -> Result<[u8; 96], CryptoError> {
+    let mut result = AggregateSignature::from_signature(&signature(&IDENTITY)?);
+    for (sig, scalar) in sigs.iter().zip(scalars) {
+        let sig = signature(sig)?;
+        sig.validate(false).map_err(blst_error)?;
+        result.add_aggregate(&mul(&AggregateSignature::from_signature(&sig), scalar)?);
+    }
+
+    Ok(result.to_signature().compress())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoding_invalid_signature_gives_error() {
+        let bytes: [u8; 96] = [
+            212, 164, 10, 104, 205, 205, 23, 255, 218, 184, 156, 159, 150, 133, 185, 31, 221, 34,
+            11, 39, 189, 17, 16, 28, 109, 72, 109, 48, 239, 105, 121, 121, 100, 149, 1, 168, 106,
+            118, 145, 148, 182, 122, 206, 83, 8, 214, 146, 238, 181, 41, 182, 23, 221, 66, 47, 99,
+            179, 9, 195, 96, 141, 204, 99, 53, 222, 157, 64, 102, 177, 118, 26, 240, 235, 189, 109,
+            214, 229, 77, 77, 24, 53, 136, 220, 124, 102, 108, 5, 241, 185, 98, 145, 206, 121, 169,
+            11, 255,
+        ];
+
+        let sig = BlsSignature(bytes);
+        let sig = min_pk::Signature::try_from(&sig);
+
+        assert!(matches!(sig, Err(CryptoError::InvalidSignature)));
+    }
+
+    #[test]
+    fn decoding_valid_signature_is_ok() {
+        let bytes: [u8; 96] = [
+            149, 240, 234, 160, 166, 30, 18, 15, 229, 113, 68, 192, 204, 118, 169, 78, 252, 237,
+            251, 111, 240, 127, 236, 68, 231, 114, 243, 76, 61, 156, 148, 34, 203, 153, 6, 255,
+            159, 108, 21, 71, 163, 120, 87, 133, 239, 135, 225, 127, 14, 126, 215, 20, 107, 206,
+            222, 198, 187, 11, 173, 56, 167, 119, 182, 55, 57, 102, 180, 194, 18, 91, 49, 59, 130,
+            39, 33, 103, 243, 211, 156, 164, 53, 160, 255, 198, 58, 2, 124, 121, 201, 44, 139, 167,
+            48, 52, 211, 178,
+        ];
+
+        let sig = BlsSignature(bytes);
+        let sig = min_pk::Signature::try_from(&sig);
+
+        assert!(sig.is_ok());
+    }
+
+    #[test]
+    fn return_error_on_invalid_public_key_encoding() {
+        let bytes: [u8; 48] = [
+            118, 187, 155, 125, 42, 190, 144, 143, 145, 250, 125, 184, 90, 9, 210, 24, 202, 72, 22,
+            137, 121, 174, 233, 107, 175, 63, 167, 107, 192, 38, 60, 102, 74, 213, 169, 88, 51,
+            181, 190, 79, 226, 209, 166, 137, 54, 88, 14, 28,
+        ];
+
+        let pk = PublicKeyBls(bytes);
+        let pk = min_pk::PublicKey::try_from(&pk);
+
+        assert!(matches!(pk, Err(CryptoError::InvalidPublicKey)));
+    }
+
+    #[test]
+    fn decode_valid_public_key_is_ok() {
+        let bytes: [u8; 48] = [
+            145, 150, 3, 232, 142, 40, 236, 191, 116, 53, 15, 47, 240, 143, 182, 94, 110, 121, 160,
+            108, 247, 42, 34, 231, 133, 156, 81, 111, 62, 109, 59, 223, 198, 220, 89, 7, 173, 251,
+            241, 82, 161, 86, 161, 40, 141, 57, 145, 123,
+        ];
+
+        let pk = PublicKeyBls(bytes);
+        let pk = min_pk::PublicKey::try_from(&pk);
+
+        assert!(pk.is_ok());
+    }
+}
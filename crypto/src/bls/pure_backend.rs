@@ -0,0 +1,469 @@
+// SPDX-FileCopyrightText: 2024 Trilitech <contact@trili.tech>
+//
+// SPDX-License-Identifier: MIT
+
+//! Pure-Rust BLS backend, based on `bls12_381`.
+//!
+//! Mirrors the checks of the `blst` backend, so that both give the same
+//! results: points are decoded without subgroup check, which is done when
+//! verifying or aggregating instead.
+
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{
+    multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, Scalar,
+};
+use cryptoxide::hkdf::{hkdf_expand, hkdf_extract};
+use cryptoxide::sha2::Sha256;
+use zeroize::Zeroize;
+
+use crate::CryptoError;
+
+// Same errors as the `blst` backend.
+const BAD_ENCODING: &str = "BLST_ERROR: BLST_BAD_ENCODING";
+const POINT_NOT_IN_GROUP: &str = "BLST_ERROR: BLST_POINT_NOT_IN_GROUP";
+const PK_IS_INFINITY: &str = "BLST_ERROR: BLST_PK_IS_INFINITY";
+const AGGR_TYPE_MISMATCH: &str = "BLST_ERROR: BLST_AGGR_TYPE_MISMATCH";
+
+fn error(e: &str) -> CryptoError {
+    CryptoError::AlgorithmError(e.to_string())
+}
+
+// Secret keys are big-endian encoded, non-zero scalars.
+fn secret_key(sk: &[u8]) -> Result<Scalar, CryptoError> {
+    let mut bytes: [u8; 32] = sk.try_into().map_err(|_| error(BAD_ENCODING))?;
+    bytes.reverse();
+    let scalar = Option::<Scalar>::from(Scalar::from_bytes(&bytes));
+    bytes.zeroize();
+
+    scalar
+        .filter(|scalar| *scalar != Scalar::zero())
+        .ok_or_else(|| error(BAD_ENCODING))
+}
+
+fn secret_key_bytes(scalar: &Scalar) -> Vec<u8> {
+    let mut bytes = scalar.to_bytes();
+    bytes.reverse();
+    bytes.to_vec()
+}
+
+// Like `blst`, the points with `x = 0` are rejected when decoding.
+fn is_zero_x(bytes: &[u8]) -> bool {
+    bytes[0] & 0x40 == 0 && bytes[0] & 0x1f == 0 && bytes[1..].iter().all(|b| *b == 0)
+}
+
+fn public_key(pk: &[u8]) -> Result<G1Affine, CryptoError> {
+    let bytes: &[u8; 48] = pk.try_into().map_err(|_| CryptoError::InvalidPublicKey)?;
+    if is_zero_x(bytes) {
+        return Err(CryptoError::InvalidPublicKey);
+    }
+    Option::from(G1Affine::from_compressed_unchecked(bytes)).ok_or(CryptoError::InvalidPublicKey)
+}
+
+fn validate_public_key(pk: &G1Affine) -> Result<(), CryptoError> {
+    if bool::from(pk.is_identity()) {
+        Err(error(PK_IS_INFINITY))
+    } else if !bool::from(pk.is_torsion_free()) {
+        Err(error(POINT_NOT_IN_GROUP))
+    } else {
+        Ok(())
+    }
+}
+
+fn signature(sig: &[u8]) -> Result<G2Affine, CryptoError> {
+    let bytes: &[u8; 96] = sig.try_into().map_err(|_| CryptoError::InvalidSignature)?;
+    if is_zero_x(bytes) {
+        return Err(CryptoError::InvalidSignature);
+    }
+    Option::from(G2Affine::from_compressed_unchecked(bytes)).ok_or(CryptoError::InvalidSignature)
+}
+
+fn hash_to_g2(msg: &[u8], dst: &[u8]) -> G2Affine {
+    <G2Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(msg, dst).into()
+}
+
+// Whether the product of the pairings of `terms` is the identity.
+fn pairings_are_identity(terms: &[(G1Affine, G2Affine)]) -> bool {
+    let prepared = terms
+        .iter()
+        .map(|(p, q)| (p, G2Prepared::from(*q)))
+        .collect::<Vec<_>>();
+    let terms = prepared.iter().map(|(p, q)| (*p, q)).collect::<Vec<_>>();
+
+    multi_miller_loop(&terms).final_exponentiation() == Gt::identity()
+}
+
+/// `KeyGen` of draft-irtf-cfrg-bls-signature-04, with an empty `key_info`.
+pub(super) fn key_gen(ikm: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if ikm.len() < 32 {
+        return Err(error(BAD_ENCODING));
+    }
+
+    let mut ikm = [ikm, &[0]].concat();
+    // key_info || I2OSP(L, 2), with L = 48
+    let info = [0, 48];
+    let mut salt = cryptoxide::hashing::sha256(b"BLS-SIG-KEYGEN-SALT-");
+    let mut prk = [0; 32];
+    let mut okm = [0; 48];
+
+    let sk = loop {
+        hkdf_extract(Sha256::new(), &salt, &ikm, &mut prk);
+        hkdf_expand(Sha256::new(), &prk, &info, &mut okm);
+
+        // OS2IP(OKM) mod r
+        let mut wide = [0; 64];
+        wide[..48].copy_from_slice(&okm);
+        wide[..48].reverse();
+        let sk = Scalar::from_bytes_wide(&wide);
+        wide.zeroize();
+
+        if sk != Scalar::zero() {
+            break sk;
+        }
+        salt = cryptoxide::hashing::sha256(&salt);
+    };
+
+    ikm.zeroize();
+    prk.zeroize();
+    okm.zeroize();
+
+    Ok(secret_key_bytes(&sk))
+}
+
+pub(super) fn sk_to_pk(sk: &[u8]) -> Result<[u8; 48], CryptoError> {
+    let pk = G1Affine::from(G1Affine::generator() * secret_key(sk)?);
+    Ok(pk.to_compressed())
+}
+
+pub(super) fn sign(sk: &[u8], msg: &[u8], dst: &[u8]) -> Result<[u8; 96], CryptoError> {
+    let sig = G2Affine::from(hash_to_g2(msg, dst) * secret_key(sk)?);
+    Ok(sig.to_compressed())
+}
+
+pub(super) fn aggregate_verify(
+    sig: &[u8],
+    msgs: &[&[u8]],
+    pks: &[&[u8]],
+    dst: &[u8],
+) -> Result<bool, CryptoError> {
+    let signature = signature(sig)?;
+    let public_keys = pks
+        .iter()
+        .map(|pk| public_key(pk))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if public_keys.is_empty()
+        || msgs.len() != public_keys.len()
+        || public_keys
+            .iter()
+            .any(|pk| validate_public_key(pk).is_err())
+        || !bool::from(signature.is_torsion_free())
+    {
+        return Ok(false);
+    }
+
+    let mut terms = msgs
+        .iter()
+        .zip(public_keys)
+        .map(|(msg, pk)| (pk, hash_to_g2(msg, dst)))
+        .collect::<Vec<_>>();
+    terms.push((-G1Affine::generator(), signature));
+
+    Ok(pairings_are_identity(&terms))
+}
+
+pub(super) fn verify_multiple(
+    msgs: &[&[u8]],
+    pks: &[&[u8]],
+    sigs: &[&[u8]],
+    rands: &[u64],
+    dst: &[u8],
+) -> Result<bool, CryptoError> {
+    let public_keys = pks
+        .iter()
+        .map(|pk| public_key(pk))
+        .collect::<Result<Vec<_>, _>>()?;
+    let signatures = sigs
+        .iter()
+        .map(|sig| signature(sig))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let n = public_keys.len();
+    if n == 0
+        || msgs.len() != n
+        || signatures.len() != n
+        || rands.len() != n
+        || public_keys
+            .iter()
+            .any(|pk| validate_public_key(pk).is_err())
+        || signatures
+            .iter()
+            .any(|sig| !bool::from(sig.is_torsion_free()))
+    {
+        return Ok(false);
+    }
+
+    // sum(r_i * sig_i) is checked against the r_i * pk_i.
+    let mut aggregate = G2Projective::identity();
+    let mut terms = Vec::with_capacity(n + 1);
+    for i in 0..n {
+        let rand = Scalar::from(rands[i]);
+        aggregate += signatures[i] * rand;
+        terms.push((
+            G1Affine::from(public_keys[i] * rand),
+            hash_to_g2(msgs[i], dst),
+        ));
+    }
+    terms.push((-G1Affine::generator(), G2Affine::from(aggregate)));
+
+    Ok(pairings_are_identity(&terms))
+}
+
+pub(super) fn aggregate_signatures(sigs: &[&[u8]]) -> Result<[u8; 96], CryptoError> {
+    let sigs = sigs
+        .iter()
+        .map(|sig| signature(sig))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if sigs.is_empty() {
+        return Err(error(AGGR_TYPE_MISMATCH));
+    }
+
+    let mut aggregate = G2Projective::identity();
+    for sig in sigs {
+        if !bool::from(sig.is_torsion_free()) {
+            return Err(error(POINT_NOT_IN_GROUP));
+        }
+        aggregate += sig;
+    }
+
+    Ok(G2Affine::from(aggregate).to_compressed())
+}
+
+pub(super) fn aggregate_public_keys(pks: &[&[u8]]) -> Result<[u8; 48], CryptoError> {
+    let pks = pks
+        .iter()
+        .map(|pk| public_key(pk))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if pks.is_empty() {
+        return Err(error(AGGR_TYPE_MISMATCH));
+    }
+
+    let mut aggregate = G1Projective::identity();
+    for pk in pks {
+        validate_public_key(&pk)?;
+        aggregate += pk;
+    }
+
+    Ok(G1Affine::from(aggregate).to_compressed())
+}
+
+/// Sum of the signatures multiplied by the big-endian `scalars`.
+pub(super) fn linear_combination(
+    sigs: &[&[u8]],
+    scalars: &[[u8; 32]],
+) -> Result<[u8; 96], CryptoError> {
+    let mut result = G2Projective::identity();
+    for (sig, scalar) in sigs.iter().zip(scalars) {
+        let sig = signature(sig)?;
+        if !bool::from(sig.is_torsion_free()) {
+            return Err(error(POINT_NOT_IN_GROUP));
+        }
+        let mut wide = [0; 64];
+        wide[..32].copy_from_slice(scalar);
+        wide[..32].reverse();
+        result += sig * Scalar::from_bytes_wide(&wide);
+    }
+
+    Ok(G2Affine::from(result).to_compressed())
+}
+
+#[cfg(all(test, feature = "bls"))]
+mod tests {
+    use super::super::blst_backend as blst;
+    use super::*;
+    use proptest::prelude::*;
+
+    const DSTS: [&[u8]; 3] = [
+        b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_",
+        b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_AUG_",
+        b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_",
+    ];
+
+    // The point (0, 2): on the curve, but rejected when decoding.
+    const PK_ZERO_X: [u8; 48] = {
+        let mut bytes = [0; 48];
+        bytes[0] = 0x80;
+        bytes
+    };
+
+    // Find a point on the curve, but not in the subgroup.
+    fn pk_not_in_group() -> [u8; 48] {
+        (1..=u8::MAX)
+            .map(|x| {
+                let mut bytes = PK_ZERO_X;
+                bytes[47] = x;
+                bytes
+            })
+            .find(|bytes| {
+                Option::<G1Affine>::from(G1Affine::from_compressed_unchecked(bytes))
+                    .map_or(false, |pk| !bool::from(pk.is_torsion_free()))
+            })
+            .unwrap()
+    }
+
+    fn identity_pk() -> Vec<u8> {
+        G1Affine::identity().to_compressed().to_vec()
+    }
+
+    fn same<T: PartialEq + std::fmt::Debug>(
+        pure: Result<T, CryptoError>,
+        blst: Result<T, CryptoError>,
+    ) {
+        match (pure, blst) {
+            (Ok(pure), Ok(blst)) => assert_eq!(pure, blst),
+            (Err(pure), Err(blst)) => assert_eq!(pure.to_string(), blst.to_string()),
+            (pure, blst) => panic!("backends disagree: {:?} vs {:?}", pure, blst),
+        }
+    }
+
+    #[test]
+    fn invalid_encodings() {
+        let bad_sig = [0xd4; 96];
+        let bad_pk = [0x76; 48];
+        let (sk, sig) = {
+            let sk = key_gen(&[1; 32]).unwrap();
+            let sig = sign(&sk, b"msg", DSTS[0]).unwrap();
+            (sk, sig)
+        };
+        let pk = sk_to_pk(&sk).unwrap();
+        let pk_not_in_group = pk_not_in_group();
+
+        same(sk_to_pk(&[0; 32]), blst::sk_to_pk(&[0; 32]));
+        same(sk_to_pk(&[0xff; 32]), blst::sk_to_pk(&[0xff; 32]));
+        same(key_gen(&[1; 16]), blst::key_gen(&[1; 16]));
+
+        same(
+            aggregate_signatures(&[&sig, &bad_sig]),
+            blst::aggregate_signatures(&[&sig, &bad_sig]),
+        );
+        same(aggregate_signatures(&[]), blst::aggregate_signatures(&[]));
+        same(
+            aggregate_public_keys(&[&pk, &bad_pk]),
+            blst::aggregate_public_keys(&[&pk, &bad_pk]),
+        );
+        same(
+            aggregate_public_keys(&[&pk, &identity_pk()]),
+            blst::aggregate_public_keys(&[&pk, &identity_pk()]),
+        );
+        same(
+            aggregate_public_keys(&[&pk, &PK_ZERO_X]),
+            blst::aggregate_public_keys(&[&pk, &PK_ZERO_X]),
+        );
+        same(
+            aggregate_public_keys(&[&pk, &pk_not_in_group]),
+            blst::aggregate_public_keys(&[&pk, &pk_not_in_group]),
+        );
+        same(aggregate_public_keys(&[]), blst::aggregate_public_keys(&[]));
+
+        for pks in [
+            vec![&bad_pk[..]],
+            vec![&identity_pk()[..]],
+            vec![&PK_ZERO_X[..]],
+            vec![&pk_not_in_group[..]],
+            vec![],
+        ] {
+            let msgs = vec![&b"msg"[..]; pks.len()];
+            same(
+                aggregate_verify(&sig, &msgs, &pks, DSTS[0]),
+                blst::aggregate_verify(&sig, &msgs, &pks, DSTS[0]),
+            );
+            let sigs = vec![&sig[..]; pks.len()];
+            let rands = vec![1; pks.len()];
+            same(
+                verify_multiple(&msgs, &pks, &sigs, &rands, DSTS[0]),
+                blst::verify_multiple(&msgs, &pks, &sigs, &rands, DSTS[0]),
+            );
+        }
+        same(
+            aggregate_verify(&bad_sig, &[b"msg"], &[&pk], DSTS[0]),
+            blst::aggregate_verify(&bad_sig, &[b"msg"], &[&pk], DSTS[0]),
+        );
+    }
+
+    proptest! {
+      // The pure backend is slow in debug builds.
+      #![proptest_config(ProptestConfig::with_cases(16))]
+
+      #[test]
+      fn backends_agree(
+          ikms in proptest::collection::vec(any::<[u8; 32]>(), 1..4),
+          msgs in proptest::collection::vec(any::<Vec<u8>>(), 4),
+          dst in 0..DSTS.len(),
+          rand in any::<u64>(),
+      ) {
+          let dst = DSTS[dst];
+          let n = ikms.len();
+          let msgs = msgs[..n].iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+          let sks = ikms.iter().map(|ikm| key_gen(ikm).unwrap()).collect::<Vec<_>>();
+          let pks = sks.iter().map(|sk| sk_to_pk(sk).unwrap()).collect::<Vec<_>>();
+          let sigs = sks
+              .iter()
+              .zip(&msgs)
+              .map(|(sk, msg)| sign(sk, msg, dst).unwrap())
+              .collect::<Vec<_>>();
+
+          for (i, ikm) in ikms.iter().enumerate() {
+              same(Ok(sks[i].clone()), blst::key_gen(ikm));
+              same(Ok(pks[i]), blst::sk_to_pk(&sks[i]));
+              same(Ok(sigs[i]), blst::sign(&sks[i], msgs[i], dst));
+          }
+
+          let pks = pks.iter().map(|pk| &pk[..]).collect::<Vec<_>>();
+          let sigs = sigs.iter().map(|sig| &sig[..]).collect::<Vec<_>>();
+          let rands = (0..n as u64).map(|i| rand.wrapping_add(i) | 1).collect::<Vec<_>>();
+
+          let aggregate = aggregate_signatures(&sigs).unwrap();
+          same(Ok(aggregate), blst::aggregate_signatures(&sigs));
+          same(aggregate_public_keys(&pks), blst::aggregate_public_keys(&pks));
+
+          let scalars = rands
+              .iter()
+              .map(|rand| {
+                  let mut scalar = [0; 32];
+                  scalar[24..].copy_from_slice(&rand.to_be_bytes());
+                  scalar
+              })
+              .collect::<Vec<_>>();
+          same(
+              linear_combination(&sigs, &scalars),
+              blst::linear_combination(&sigs, &scalars),
+          );
+
+          same(
+              aggregate_verify(&aggregate, &msgs, &pks, dst),
+              blst::aggregate_verify(&aggregate, &msgs, &pks, dst),
+          );
+          assert!(matches!(aggregate_verify(&aggregate, &msgs, &pks, dst), Ok(true)));
+          same(
+              verify_multiple(&msgs, &pks, &sigs, &rands, dst),
+              blst::verify_multiple(&msgs, &pks, &sigs, &rands, dst),
+          );
+
+          // Tampered: the first signature is over another message.
+          let tampered = sign(&sks[0], b"tampered", dst).unwrap();
+          let mut tampered_sigs = sigs.clone();
+          tampered_sigs[0] = &tampered;
+          let tampered_aggregate = aggregate_signatures(&tampered_sigs).unwrap();
+          same(
+              aggregate_verify(&tampered_aggregate, &msgs, &pks, dst),
+              blst::aggregate_verify(&tampered_aggregate, &msgs, &pks, dst),
+          );
+          same(
+              verify_multiple(&msgs, &pks, &tampered_sigs, &rands, dst),
+              blst::verify_multiple(&msgs, &pks, &tampered_sigs, &rands, dst),
+          );
+      }
+    }
+}
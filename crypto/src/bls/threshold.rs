@@ -0,0 +1,268 @@
+// SPDX-FileCopyrightText: 2024 Trilitech <contact@trili.tech>
+//
+// SPDX-License-Identifier: MIT
+
+//! Threshold BLS signatures.
+//!
+//! A secret key is split into `n` shares with Shamir secret sharing, such
+//! that any `t` of them sign for it: the partial signatures of `t` shares
+//! combine, by Lagrange interpolation, into the signature of the secret key.
+//!
+//! A share is its index `x`, followed by `f(x)`, where `f` is a random
+//! polynomial of degree `t - 1` with `f(0)` the secret key.
+
+use bls12_381::Scalar;
+use zeroize::{Zeroize, Zeroizing};
+
+use super::{backend, BlsScheme};
+use crate::hash::{BlsPartialSignature, BlsSignature, PublicKeyBls, SecretKeyBls};
+use crate::hash::{HashType, SecretKeyBlsShare};
+use crate::CryptoError;
+
+// Shares are computed in the scalar field of `bls12_381` with either backend,
+// as `blst` only exposes its own through unsafe bindings.
+
+const INDEX_SIZE: usize = 2;
+
+// Reduced from at most 64 big-endian bytes.
+fn scalar_from_be_bytes(bytes: &[u8]) -> Zeroizing<Scalar> {
+    let mut wide = [0; 64];
+    wide[..bytes.len()].copy_from_slice(bytes);
+    wide[..bytes.len()].reverse();
+    let scalar = Zeroizing::new(Scalar::from_bytes_wide(&wide));
+    wide.zeroize();
+    scalar
+}
+
+// Big-endian, as secret keys.
+fn scalar_bytes(scalar: &Scalar) -> [u8; 32] {
+    let mut bytes = scalar.to_bytes();
+    bytes.reverse();
+    bytes
+}
+
+// Lagrange coefficients at 0 of the given distinct, non-zero indices.
+fn lagrange_coefficients(indices: &[u16]) -> Vec<[u8; 32]> {
+    indices
+        .iter()
+        .map(|&i| {
+            let (num, den) = indices.iter().filter(|&&j| j != i).fold(
+                (Scalar::one(), Scalar::one()),
+                |(num, den), &j| {
+                    let (i, j) = (Scalar::from(i as u64), Scalar::from(j as u64));
+                    (num * j, den * (j - i))
+                },
+            );
+            // The indices are distinct, so `den` is not zero.
+            scalar_bytes(&(num * den.invert().unwrap()))
+        })
+        .collect()
+}
+
+impl SecretKeyBls {
+    /// Split the secret key into `shares` shares, any `threshold` of which
+    /// sign for it.
+    ///
+    /// Shares are indexed from 1 to `shares`.
+    pub fn split<R>(
+        &self,
+        threshold: u16,
+        shares: u16,
+        rng: &mut R,
+    ) -> Result<Vec<SecretKeyBlsShare>, CryptoError>
+    where
+        R: rand::RngCore + rand::CryptoRng,
+    {
+        if threshold == 0 || threshold > shares {
+            return Err(CryptoError::AlgorithmError(format!(
+                "invalid threshold {} of {} shares",
+                threshold, shares
+            )));
+        }
+
+        let mut coefficients = vec![scalar_from_be_bytes(&self.0)];
+        for _ in 1..threshold {
+            // Reduced from 64 bytes, to be uniform.
+            let mut bytes = [0; 64];
+            rng.fill_bytes(&mut bytes);
+            coefficients.push(scalar_from_be_bytes(&bytes));
+            bytes.zeroize();
+        }
+
+        let shares = (1..=shares)
+            .map(|index| {
+                // Horner's method.
+                let x = Scalar::from(index as u64);
+                let mut value = Zeroizing::new(Scalar::zero());
+                for coefficient in coefficients.iter().rev() {
+                    *value = *value * x + **coefficient;
+                }
+
+                let mut value_bytes = scalar_bytes(&value);
+                let mut share = index.to_be_bytes().to_vec();
+                share.extend_from_slice(&value_bytes);
+                value_bytes.zeroize();
+                SecretKeyBlsShare(share)
+            })
+            .collect();
+
+        Ok(shares)
+    }
+}
+
+impl SecretKeyBlsShare {
+    /// Index of the share, from 1.
+    pub fn index(&self) -> u16 {
+        u16::from_be_bytes([self.0[0], self.0[1]])
+    }
+
+    /// Partially sign `message` for `public_key`, the public key of the
+    /// shared secret key.
+    ///
+    /// Like [SecretKeyBls::sign], signing uses the Aug suite.
+    pub fn sign(
+        &self,
+        public_key: &PublicKeyBls,
+        message: impl AsRef<[u8]>,
+    ) -> Result<BlsPartialSignature, CryptoError> {
+        self.sign_with_scheme(BlsScheme::Aug, public_key, message)
+    }
+
+    /// Partially sign `message` for `public_key` in the given scheme.
+    pub fn sign_with_scheme(
+        &self,
+        scheme: BlsScheme,
+        public_key: &PublicKeyBls,
+        message: impl AsRef<[u8]>,
+    ) -> Result<BlsPartialSignature, CryptoError> {
+        let msg = scheme.message(message.as_ref(), public_key);
+        let sig = backend::sign(&self.0[INDEX_SIZE..], &msg, scheme.dst())?;
+
+        let mut partial = [0; HashType::BlsPartialSignature.size()];
+        partial[..INDEX_SIZE].copy_from_slice(&self.0[..INDEX_SIZE]);
+        partial[INDEX_SIZE..].copy_from_slice(&sig);
+        Ok(BlsPartialSignature(partial))
+    }
+}
+
+impl BlsPartialSignature {
+    /// Index of the share which signed.
+    pub fn index(&self) -> u16 {
+        u16::from_be_bytes([self.0[0], self.0[1]])
+    }
+}
+
+impl BlsSignature {
+    /// Combine partial signatures of the same message into the signature of
+    /// the shared secret key.
+    ///
+    /// Fails if the indices are not distinct. With fewer partial signatures
+    /// than the threshold, the signature does not verify.
+    pub fn combine(partials: &[&BlsPartialSignature]) -> Result<Self, CryptoError> {
+        let indices = partials.iter().map(|p| p.index()).collect::<Vec<_>>();
+        let distinct = indices.iter().collect::<std::collections::BTreeSet<_>>();
+        if partials.is_empty() || distinct.len() != indices.len() || distinct.contains(&0) {
+            return Err(CryptoError::AlgorithmError(
+                "partial signatures must have distinct, non-zero indices".to_string(),
+            ));
+        }
+
+        let signatures = partials
+            .iter()
+            .map(|p| &p.0[INDEX_SIZE..])
+            .collect::<Vec<_>>();
+        let coefficients = lagrange_coefficients(&indices);
+
+        backend::linear_combination(&signatures, &coefficients).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::HashTrait;
+    use crate::PublicKeySignatureVerifier;
+    use tezos_data_encoding::enc::BinWriter;
+    use tezos_data_encoding::nom::NomReader;
+
+    #[test]
+    fn threshold_signature() {
+        let mut rng = rand::thread_rng();
+        let sk = SecretKeyBls::generate(&mut rng).unwrap();
+        let pk = sk.derive_pk().unwrap();
+        let msg = b"threshold";
+
+        let shares = sk.split(3, 5, &mut rng).unwrap();
+        assert_eq!(
+            shares.iter().map(|s| s.index()).collect::<Vec<_>>(),
+            [1, 2, 3, 4, 5]
+        );
+
+        let partials = shares
+            .iter()
+            .map(|share| share.sign(&pk, msg).unwrap())
+            .collect::<Vec<_>>();
+
+        // Any 3 shares give the signature of the secret key.
+        let expected = sk.sign(msg).unwrap();
+        for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let subset = subset.map(|i| &partials[i]);
+            let sig = BlsSignature::combine(&subset).unwrap();
+            assert_eq!(sig, expected);
+            assert!(pk.verify_signature(&sig, msg).unwrap());
+        }
+
+        // 2 shares do not.
+        let sig = BlsSignature::combine(&[&partials[0], &partials[3]]).unwrap();
+        assert!(!pk.verify_signature(&sig, msg).unwrap());
+
+        // Indices must be distinct.
+        assert!(BlsSignature::combine(&[&partials[0], &partials[0], &partials[1]]).is_err());
+        assert!(BlsSignature::combine(&[]).is_err());
+    }
+
+    #[test]
+    fn split_threshold() {
+        let mut rng = rand::thread_rng();
+        let sk = SecretKeyBls::generate(&mut rng).unwrap();
+
+        assert!(sk.split(0, 3, &mut rng).is_err());
+        assert!(sk.split(4, 3, &mut rng).is_err());
+
+        // With a threshold of 1, every share is the secret key.
+        let shares = sk.split(1, 2, &mut rng).unwrap();
+        assert!(shares.iter().all(|share| share.0[INDEX_SIZE..] == sk.0[..]));
+    }
+
+    #[test]
+    fn share_encodings() {
+        let mut rng = rand::thread_rng();
+        let sk = SecretKeyBls::generate(&mut rng).unwrap();
+        let pk = sk.derive_pk().unwrap();
+
+        let share = sk.split(2, 3, &mut rng).unwrap().remove(2);
+        let partial = share.sign(&pk, b"message").unwrap();
+
+        let encoded = share.to_b58check();
+        assert!(encoded.starts_with("BLssk"));
+        assert_eq!(SecretKeyBlsShare::from_b58check(&encoded).unwrap(), share);
+
+        let encoded = partial.to_b58check();
+        assert!(encoded.starts_with("BLpsig"));
+        assert_eq!(
+            BlsPartialSignature::from_b58check(&encoded).unwrap(),
+            partial
+        );
+
+        let mut bytes = vec![];
+        share.bin_write(&mut bytes).unwrap();
+        partial.bin_write(&mut bytes).unwrap();
+        let (rest, decoded) = SecretKeyBlsShare::nom_read(&bytes).unwrap();
+        assert_eq!(decoded, share);
+        assert_eq!(decoded.index(), 3);
+        let (rest, decoded) = BlsPartialSignature::nom_read(rest).unwrap();
+        assert_eq!(decoded, partial);
+        assert_eq!(decoded.index(), 3);
+        assert!(rest.is_empty());
+    }
+}
@@ -0,0 +1,638 @@
+// SPDX-FileCopyrightText: 2024 TriliTech <contact@trili.tech>
+//
+// SPDX-License-Identifier: MIT
+
+//! Layer1 contracts, destinations and addresses.
+//!
+//! A [Contract] is either an implicit account or an originated contract. A
+//! [Destination] also covers smart rollups, and an [Address] is a destination
+//! with an [Entrypoint], as in `"KT1...%entrypoint"`.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::combinator::map;
+use nom::sequence::{preceded, terminated};
+use tezos_data_encoding::enc::{self, BinResult, BinWriter};
+use tezos_data_encoding::encoding::{Encoding, HasEncoding};
+use tezos_data_encoding::nom::{NomReader, NomResult};
+use thiserror::Error;
+
+use crate::base58::FromBase58CheckError;
+use crate::hash::{AnyHash, ContractKt1Hash, HashTrait, Network, SmartRollupHash};
+use crate::public_key_hash::PublicKeyHash;
+
+// Tags of the binary encodings.
+const IMPLICIT_TAG: u8 = 0;
+const ORIGINATED_TAG: u8 = 1;
+const SMART_ROLLUP_TAG: u8 = 3;
+
+// Originated contracts and smart rollups are padded to the size of implicit
+// accounts.
+const PADDING: u8 = 0;
+
+/// Maximum length of an entrypoint name.
+pub const ENTRYPOINT_MAX_LENGTH: usize = 31;
+
+/// Errors of parsing addresses.
+#[derive(Debug, Error)]
+pub enum AddressError {
+    /// Invalid base58check encoding of the contract.
+    #[error("Invalid contract: {0}")]
+    Base58(#[from] FromBase58CheckError),
+    /// Invalid entrypoint name.
+    #[error("Invalid entrypoint `{0}`")]
+    InvalidEntrypoint(String),
+}
+
+/// Layer1 contract: an implicit account or an originated contract.
+///
+/// Encoded in 22 bytes: a tag, followed by the public key hash of implicit
+/// accounts, or by the contract hash and a padding byte of originated
+/// contracts.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Contract {
+    /// Implicit account: tz1, tz2, tz3 or tz4.
+    Implicit(PublicKeyHash),
+    /// Originated contract: KT1.
+    Originated(ContractKt1Hash),
+}
+
+impl Contract {
+    /// Conversion from base58-encoding string (with prefix).
+    ///
+    /// Accepts the prefixes of every [Network].
+    pub fn from_b58check(data: &str) -> Result<Self, FromBase58CheckError> {
+        Destination::from_b58check(data)?
+            .try_into()
+            .map_err(|_| FromBase58CheckError::IncorrectBase58Prefix)
+    }
+
+    /// Conversion to base58-encoding string (with prefix).
+    pub fn to_b58check(&self) -> String {
+        self.to_b58check_for(Network::DEFAULT)
+    }
+
+    /// Conversion to base58-encoding string, with the prefix of the given
+    /// network.
+    pub fn to_b58check_for(&self, network: Network) -> String {
+        match self {
+            Self::Implicit(pkh) => pkh.to_b58check_for(network),
+            Self::Originated(kt1) => kt1.to_b58check_for(network),
+        }
+    }
+}
+
+impl Display for Contract {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_b58check())
+    }
+}
+
+impl From<PublicKeyHash> for Contract {
+    fn from(pkh: PublicKeyHash) -> Self {
+        Self::Implicit(pkh)
+    }
+}
+
+impl From<ContractKt1Hash> for Contract {
+    fn from(kt1: ContractKt1Hash) -> Self {
+        Self::Originated(kt1)
+    }
+}
+
+impl TryFrom<&str> for Contract {
+    type Error = FromBase58CheckError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_b58check(value)
+    }
+}
+
+impl FromStr for Contract {
+    type Err = FromBase58CheckError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_b58check(s)
+    }
+}
+
+impl HasEncoding for Contract {
+    fn encoding() -> Encoding {
+        Encoding::Custom
+    }
+}
+
+impl<'a> NomReader<'a> for Contract {
+    fn nom_read(input: &'a [u8]) -> NomResult<'a, Self> {
+        alt((
+            map(
+                preceded(tag([IMPLICIT_TAG]), PublicKeyHash::nom_read),
+                Self::Implicit,
+            ),
+            map(
+                preceded(
+                    tag([ORIGINATED_TAG]),
+                    terminated(ContractKt1Hash::nom_read, tag([PADDING])),
+                ),
+                Self::Originated,
+            ),
+        ))(input)
+    }
+}
+
+impl BinWriter for Contract {
+    fn bin_write(&self, output: &mut Vec<u8>) -> BinResult {
+        match self {
+            Self::Implicit(pkh) => {
+                enc::u8(&IMPLICIT_TAG, output)?;
+                pkh.bin_write(output)
+            }
+            Self::Originated(kt1) => {
+                enc::u8(&ORIGINATED_TAG, output)?;
+                kt1.bin_write(output)?;
+                enc::u8(&PADDING, output)
+            }
+        }
+    }
+}
+
+/// Destination of a transaction: a [Contract] or a smart rollup.
+///
+/// Encoded in 22 bytes, as contracts, with smart rollup hashes padded as
+/// originated contracts.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Destination {
+    /// Implicit account: tz1, tz2, tz3 or tz4.
+    Implicit(PublicKeyHash),
+    /// Originated contract: KT1.
+    Originated(ContractKt1Hash),
+    /// Smart rollup: sr1.
+    SmartRollup(SmartRollupHash),
+}
+
+impl Destination {
+    /// Conversion from base58-encoding string (with prefix).
+    ///
+    /// Accepts the prefixes of every [Network].
+    pub fn from_b58check(data: &str) -> Result<Self, FromBase58CheckError> {
+        match AnyHash::from_b58check(data)? {
+            AnyHash::ContractTz1Hash(tz1) => Ok(Self::Implicit(PublicKeyHash::Ed25519(tz1))),
+            AnyHash::ContractTz2Hash(tz2) => Ok(Self::Implicit(PublicKeyHash::Secp256k1(tz2))),
+            AnyHash::ContractTz3Hash(tz3) => Ok(Self::Implicit(PublicKeyHash::P256(tz3))),
+            AnyHash::ContractTz4Hash(tz4) => Ok(Self::Implicit(PublicKeyHash::Bls(tz4))),
+            AnyHash::ContractKt1Hash(kt1) => Ok(Self::Originated(kt1)),
+            AnyHash::SmartRollupHash(sr1) => Ok(Self::SmartRollup(sr1)),
+            _ => Err(FromBase58CheckError::IncorrectBase58Prefix),
+        }
+    }
+
+    /// Conversion to base58-encoding string (with prefix).
+    pub fn to_b58check(&self) -> String {
+        self.to_b58check_for(Network::DEFAULT)
+    }
+
+    /// Conversion to base58-encoding string, with the prefix of the given
+    /// network.
+    pub fn to_b58check_for(&self, network: Network) -> String {
+        match self {
+            Self::Implicit(pkh) => pkh.to_b58check_for(network),
+            Self::Originated(kt1) => kt1.to_b58check_for(network),
+            Self::SmartRollup(sr1) => sr1.to_b58check_for(network),
+        }
+    }
+}
+
+impl Display for Destination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_b58check())
+    }
+}
+
+impl From<Contract> for Destination {
+    fn from(contract: Contract) -> Self {
+        match contract {
+            Contract::Implicit(pkh) => Self::Implicit(pkh),
+            Contract::Originated(kt1) => Self::Originated(kt1),
+        }
+    }
+}
+
+impl From<SmartRollupHash> for Destination {
+    fn from(sr1: SmartRollupHash) -> Self {
+        Self::SmartRollup(sr1)
+    }
+}
+
+impl TryFrom<Destination> for Contract {
+    type Error = SmartRollupHash;
+
+    /// Fails with the hash of smart rollups, which are not contracts.
+    fn try_from(destination: Destination) -> Result<Self, Self::Error> {
+        match destination {
+            Destination::Implicit(pkh) => Ok(Self::Implicit(pkh)),
+            Destination::Originated(kt1) => Ok(Self::Originated(kt1)),
+            Destination::SmartRollup(sr1) => Err(sr1),
+        }
+    }
+}
+
+impl TryFrom<&str> for Destination {
+    type Error = FromBase58CheckError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_b58check(value)
+    }
+}
+
+impl FromStr for Destination {
+    type Err = FromBase58CheckError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_b58check(s)
+    }
+}
+
+impl HasEncoding for Destination {
+    fn encoding() -> Encoding {
+        Encoding::Custom
+    }
+}
+
+impl<'a> NomReader<'a> for Destination {
+    fn nom_read(input: &'a [u8]) -> NomResult<'a, Self> {
+        alt((
+            map(Contract::nom_read, Self::from),
+            map(
+                preceded(
+                    tag([SMART_ROLLUP_TAG]),
+                    terminated(SmartRollupHash::nom_read, tag([PADDING])),
+                ),
+                Self::SmartRollup,
+            ),
+        ))(input)
+    }
+}
+
+impl BinWriter for Destination {
+    fn bin_write(&self, output: &mut Vec<u8>) -> BinResult {
+        match self {
+            Self::Implicit(pkh) => Contract::Implicit(pkh.clone()).bin_write(output),
+            Self::Originated(kt1) => Contract::Originated(*kt1).bin_write(output),
+            Self::SmartRollup(sr1) => {
+                enc::u8(&SMART_ROLLUP_TAG, output)?;
+                sr1.bin_write(output)?;
+                enc::u8(&PADDING, output)
+            }
+        }
+    }
+}
+
+// Entrypoints with a dedicated tag in the binary encoding, by tag.
+const RESERVED_ENTRYPOINTS: [&str; 10] = [
+    "default",
+    "root",
+    "do",
+    "set_delegate",
+    "remove_delegate",
+    "deposit",
+    "stake",
+    "unstake",
+    "finalize_unstake",
+    "set_delegate_parameters",
+];
+
+const NAMED_ENTRYPOINT_TAG: u8 = 255;
+
+/// Entrypoint of a contract.
+///
+/// Names have at most [ENTRYPOINT_MAX_LENGTH] characters, among
+/// `[a-zA-Z0-9_.%@]`. The empty name is the `default` entrypoint.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Entrypoint(String);
+
+impl Entrypoint {
+    /// Entrypoint with the given name.
+    pub fn new(name: impl Into<String>) -> Result<Self, AddressError> {
+        let name = name.into();
+        if name.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let valid_char = |c: char| c.is_ascii_alphanumeric() || "_.%@".contains(c);
+        if name.len() > ENTRYPOINT_MAX_LENGTH || !name.chars().all(valid_char) {
+            return Err(AddressError::InvalidEntrypoint(name));
+        }
+        Ok(Self(name))
+    }
+
+    /// Name of the entrypoint.
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether this is the `default` entrypoint.
+    pub fn is_default(&self) -> bool {
+        self.0 == RESERVED_ENTRYPOINTS[0]
+    }
+}
+
+impl Default for Entrypoint {
+    fn default() -> Self {
+        Self(RESERVED_ENTRYPOINTS[0].to_string())
+    }
+}
+
+impl Display for Entrypoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<&str> for Entrypoint {
+    type Error = AddressError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl FromStr for Entrypoint {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl HasEncoding for Entrypoint {
+    fn encoding() -> Encoding {
+        Encoding::Custom
+    }
+}
+
+impl<'a> NomReader<'a> for Entrypoint {
+    fn nom_read(input: &'a [u8]) -> NomResult<'a, Self> {
+        use tezos_data_encoding::nom::error::DecodeError;
+        use tezos_data_encoding::nom::{bytes, short_dynamic};
+
+        let (rest, tag) = nom::number::complete::u8(input)?;
+        if tag == NAMED_ENTRYPOINT_TAG {
+            let (rest, name) = short_dynamic(bytes)(rest)?;
+            match String::from_utf8(name).map(Self::new) {
+                Ok(Ok(entrypoint)) => Ok((rest, entrypoint)),
+                _ => Err(nom::Err::Error(DecodeError::invalid_tag(
+                    input,
+                    "named entrypoint".to_string(),
+                ))),
+            }
+        } else if let Some(name) = RESERVED_ENTRYPOINTS.get(tag as usize) {
+            Ok((rest, Self(name.to_string())))
+        } else {
+            Err(nom::Err::Error(DecodeError::unknown_tag(
+                input,
+                format!("0x{:.2X}", tag),
+            )))
+        }
+    }
+}
+
+impl BinWriter for Entrypoint {
+    fn bin_write(&self, output: &mut Vec<u8>) -> BinResult {
+        match RESERVED_ENTRYPOINTS.iter().position(|name| *name == self.0) {
+            Some(tag) => enc::u8(&(tag as u8), output),
+            None => {
+                enc::u8(&NAMED_ENTRYPOINT_TAG, output)?;
+                enc::short_dynamic(enc::bytes)(self.0.as_bytes(), output)
+            }
+        }
+    }
+}
+
+/// Address of a [Destination] entrypoint, as in `"KT1...%entrypoint"`.
+///
+/// The `default` entrypoint is omitted from the string form.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Address {
+    pub destination: Destination,
+    pub entrypoint: Entrypoint,
+}
+
+impl Address {
+    /// Address of the `default` entrypoint of `destination`.
+    pub fn new(destination: impl Into<Destination>) -> Self {
+        Self {
+            destination: destination.into(),
+            entrypoint: Entrypoint::default(),
+        }
+    }
+
+    /// Address of the given entrypoint of `destination`.
+    pub fn with_entrypoint(destination: impl Into<Destination>, entrypoint: Entrypoint) -> Self {
+        Self {
+            destination: destination.into(),
+            entrypoint,
+        }
+    }
+
+    /// Conversion from base58-encoding string, optionally followed by
+    /// `%entrypoint`.
+    pub fn from_b58check(data: &str) -> Result<Self, AddressError> {
+        let (destination, entrypoint) = match data.split_once('%') {
+            Some((_, "")) => return Err(AddressError::InvalidEntrypoint(String::new())),
+            Some((destination, entrypoint)) => (destination, Entrypoint::new(entrypoint)?),
+            None => (data, Entrypoint::default()),
+        };
+
+        Ok(Self {
+            destination: Destination::from_b58check(destination)?,
+            entrypoint,
+        })
+    }
+
+    /// Conversion to base58-encoding string, followed by `%entrypoint` unless
+    /// it is the default one.
+    pub fn to_b58check(&self) -> String {
+        self.to_b58check_for(Network::DEFAULT)
+    }
+
+    /// Conversion to base58-encoding string, with the prefix of the given
+    /// network.
+    pub fn to_b58check_for(&self, network: Network) -> String {
+        let destination = self.destination.to_b58check_for(network);
+        if self.entrypoint.is_default() {
+            destination
+        } else {
+            format!("{}%{}", destination, self.entrypoint)
+        }
+    }
+}
+
+impl Display for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_b58check())
+    }
+}
+
+impl From<Destination> for Address {
+    fn from(destination: Destination) -> Self {
+        Self::new(destination)
+    }
+}
+
+impl From<Contract> for Address {
+    fn from(contract: Contract) -> Self {
+        Self::new(contract)
+    }
+}
+
+impl TryFrom<&str> for Address {
+    type Error = AddressError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_b58check(value)
+    }
+}
+
+impl FromStr for Address {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_b58check(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn round_trip<T>(value: &T, hex: &str)
+    where
+        T: for<'a> NomReader<'a> + BinWriter + PartialEq + std::fmt::Debug,
+    {
+        let mut bin = Vec::new();
+        value
+            .bin_write(&mut bin)
+            .expect("serialization should work");
+        assert_eq!(hex, hex::encode(&bin));
+
+        let (rest, decoded) = T::nom_read(&bin).expect("deserialization should work");
+        assert!(rest.is_empty());
+        assert_eq!(value, &decoded);
+    }
+
+    #[test]
+    fn contract_encoding() {
+        let tz1 = Contract::from_b58check("tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx").unwrap();
+        assert!(matches!(tz1, Contract::Implicit(PublicKeyHash::Ed25519(_))));
+        round_trip(&tz1, "000002298c03ed7d454a101eb7022bc95f7e5f41ac78");
+
+        let kt1 = Contract::from_b58check("KT1BEqzn5Wx8uJrZNvuS9DVHmLvG9td3fDLi").unwrap();
+        assert!(matches!(kt1, Contract::Originated(_)));
+        round_trip(&kt1, "011d23c1d3d2f8a4ea5e8784b8f7ecf2ad304c0fe600");
+
+        // Unknown tag, and missing padding.
+        assert!(Contract::nom_read(
+            &hex::decode("021d23c1d3d2f8a4ea5e8784b8f7ecf2ad304c0fe600").unwrap()
+        )
+        .is_err());
+        assert!(Contract::nom_read(
+            &hex::decode("011d23c1d3d2f8a4ea5e8784b8f7ecf2ad304c0fe601").unwrap()
+        )
+        .is_err());
+    }
+
+    #[cfg(not(feature = "mavryk"))]
+    #[test]
+    fn contract_b58check() {
+        for contract in [
+            "tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx",
+            "tz2VGBaXuS6rnaa5hpC92qkgadRJKdEbeGwc",
+            "tz3WEJYwJ6pPwVbSL8FrSoAXRmFHHZTuEnMA",
+            "tz4DWZXsrP3bdPaZ5B3M3iLVoRMAyxw9oKLH",
+            "KT1BEqzn5Wx8uJrZNvuS9DVHmLvG9td3fDLi",
+        ] {
+            let parsed: Contract = contract.parse().unwrap();
+            assert_eq!(contract, parsed.to_string());
+        }
+
+        let mv1 = Contract::from_b58check("mv18Cw7psUrAAPBpXYd9CtCpHg9EgjHP9KTe").unwrap();
+        assert_eq!(mv1.to_b58check(), "tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx");
+        assert_eq!(
+            mv1.to_b58check_for(Network::Mavryk),
+            "mv18Cw7psUrAAPBpXYd9CtCpHg9EgjHP9KTe"
+        );
+
+        assert!(Contract::from_b58check("sr1UNDWPUYVeomgG15wn5jSw689EJ4RNnVQa").is_err());
+    }
+
+    #[test]
+    fn destination_encoding() {
+        let sr1 = Destination::from_b58check("sr1UNDWPUYVeomgG15wn5jSw689EJ4RNnVQa").unwrap();
+        assert!(matches!(sr1, Destination::SmartRollup(_)));
+        assert_eq!(sr1.to_string(), "sr1UNDWPUYVeomgG15wn5jSw689EJ4RNnVQa");
+        round_trip(&sr1, "03f4e47cb3c43a68b0d48e3094092ca42d713addb500");
+
+        let kt1 = Destination::from_b58check("KT1BEqzn5Wx8uJrZNvuS9DVHmLvG9td3fDLi").unwrap();
+        round_trip(&kt1, "011d23c1d3d2f8a4ea5e8784b8f7ecf2ad304c0fe600");
+        assert!(Contract::try_from(kt1).is_ok());
+        assert!(Contract::try_from(sr1).is_err());
+    }
+
+    #[test]
+    fn entrypoint_encoding() {
+        round_trip(&Entrypoint::default(), "00");
+        round_trip(&Entrypoint::new("stake").unwrap(), "06");
+        round_trip(&Entrypoint::new("mint").unwrap(), "ff046d696e74");
+
+        assert_eq!(Entrypoint::new("").unwrap(), Entrypoint::default());
+        assert!(Entrypoint::new("default").unwrap().is_default());
+        assert!(Entrypoint::new("a".repeat(32)).is_err());
+        assert!(Entrypoint::new("not-valid").is_err());
+
+        assert!(Entrypoint::nom_read(&[10]).is_err());
+        assert!(Entrypoint::nom_read(&hex::decode("ff036e6f2d").unwrap()).is_err());
+    }
+
+    #[cfg(not(feature = "mavryk"))]
+    #[test]
+    fn address_b58check() {
+        let address = Address::from_b58check("KT1BEqzn5Wx8uJrZNvuS9DVHmLvG9td3fDLi%mint").unwrap();
+        assert!(matches!(address.destination, Destination::Originated(_)));
+        assert_eq!(address.entrypoint.name(), "mint");
+        assert_eq!(
+            address.to_string(),
+            "KT1BEqzn5Wx8uJrZNvuS9DVHmLvG9td3fDLi%mint"
+        );
+
+        let address: Address = "tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx".parse().unwrap();
+        assert!(address.entrypoint.is_default());
+        assert_eq!(address.to_string(), "tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx");
+
+        // The default entrypoint is omitted.
+        let address =
+            Address::from_b58check("tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx%default").unwrap();
+        assert_eq!(address.to_string(), "tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx");
+
+        let address = Address::from_b58check("mv18Cw7psUrAAPBpXYd9CtCpHg9EgjHP9KTe%stake").unwrap();
+        assert_eq!(
+            address.to_b58check_for(Network::Mavryk),
+            "mv18Cw7psUrAAPBpXYd9CtCpHg9EgjHP9KTe%stake"
+        );
+
+        assert!(matches!(
+            Address::from_b58check("KT1BEqzn5Wx8uJrZNvuS9DVHmLvG9td3fDLi%"),
+            Err(AddressError::InvalidEntrypoint(_))
+        ));
+        assert!(matches!(
+            Address::from_b58check("KT1BEqzn5Wx8uJrZNvuS9DVHmLvG9td3fDLi%no-t"),
+            Err(AddressError::InvalidEntrypoint(_))
+        ));
+        assert!(matches!(
+            Address::from_b58check("KT1BEqzn5Wx8uJrZNvuS9DVHmLvG9td3fDLx%mint"),
+            Err(AddressError::Base58(_))
+        ));
+    }
+}
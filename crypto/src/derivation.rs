@@ -0,0 +1,553 @@
+// SPDX-FileCopyrightText: 2024 Trilitech <contact@trili.tech>
+//
+// SPDX-License-Identifier: MIT
+
+//! Key derivation from BIP39 mnemonics.
+//!
+//! Supports hierarchical derivation, along paths such as `m/44'/1729'/0'/0'`:
+//! - [SLIP-10] for ed25519 (hardened indices only).
+//! - [BIP32] for secp256k1 and P256, as specified by [SLIP-10].
+//!
+//! [SLIP-10]: <https://github.com/satoshilabs/slips/blob/master/slip-0010.md>
+//! [BIP32]: <https://github.com/bitcoin/bips/blob/master/bip-0032.mediawiki>
+
+use crate::hash::{SecretKeyEd25519, SecretKeyP256, SecretKeySecp256k1, SeedEd25519};
+use crate::secret_key::{Curve, SecretKey};
+use cryptoxide::hmac::Hmac;
+use cryptoxide::mac::Mac;
+use cryptoxide::sha2::Sha512;
+use std::fmt::Display;
+use std::str::FromStr;
+use thiserror::Error;
+use zeroize::Zeroize;
+
+/// Index from which child keys are hardened.
+pub const HARDENED_OFFSET: u32 = 0x8000_0000;
+
+/// Possible errors when deriving keys.
+#[derive(Debug, Error)]
+pub enum DerivationError {
+    /// The mnemonic is not a valid english BIP39 mnemonic.
+    #[error("invalid mnemonic: {0}")]
+    InvalidMnemonic(String),
+    /// The derivation path could not be parsed.
+    #[error("invalid derivation path: {0}")]
+    InvalidPath(String),
+    /// Ed25519 only supports hardened derivation.
+    #[error("ed25519 does not support non-hardened derivation (index {0})")]
+    NonHardenedIndex(u32),
+    /// The curve does not support hierarchical derivation.
+    #[error("unsupported curve for key derivation: {0:?}")]
+    UnsupportedCurve(Curve),
+}
+
+/// Compute the 64-byte BIP39 seed of an english mnemonic and passphrase.
+///
+/// Both are normalized to NFKD first, as required by BIP39.
+pub fn seed_from_mnemonic(mnemonic: &str, passphrase: &str) -> Result<[u8; 64], DerivationError> {
+    let mnemonic = bip39::Mnemonic::parse_in(bip39::Language::English, mnemonic)
+        .map_err(|e| DerivationError::InvalidMnemonic(e.to_string()))?;
+
+    Ok(mnemonic.to_seed(passphrase))
+}
+
+impl SeedEd25519 {
+    /// Ed25519 seed of a mnemonic, without hierarchical derivation.
+    ///
+    /// This is the first 32 bytes of the BIP39 seed, as used by fundraiser
+    /// accounts and by wallets restoring non-HD accounts.
+    pub fn from_mnemonic(mnemonic: &str, passphrase: &str) -> Result<Self, DerivationError> {
+        let mut seed = seed_from_mnemonic(mnemonic, passphrase)?;
+        let seed_ed25519 = SeedEd25519(seed[..32].to_vec());
+        seed.zeroize();
+        Ok(seed_ed25519)
+    }
+}
+
+/// A derivation path, such as `m/44'/1729'/0'/0'`.
+///
+/// Hardened indices are stored with [HARDENED_OFFSET] added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// The indices of the path.
+    pub fn indices(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+impl From<Vec<u32>> for DerivationPath {
+    fn from(indices: Vec<u32>) -> Self {
+        Self(indices)
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = DerivationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DerivationError::InvalidPath(s.to_string());
+
+        let mut segments = s.split('/');
+        if segments.next() != Some("m") {
+            return Err(invalid());
+        }
+
+        segments
+            .map(|segment| {
+                let (index, hardened) = match segment
+                    .strip_suffix('\'')
+                    .or_else(|| segment.strip_suffix('h'))
+                {
+                    Some(index) => (index, true),
+                    None => (segment, false),
+                };
+                let index: u32 = index.parse().map_err(|_| invalid())?;
+                if index >= HARDENED_OFFSET {
+                    return Err(invalid());
+                }
+                Ok(if hardened {
+                    index + HARDENED_OFFSET
+                } else {
+                    index
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl Display for DerivationPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            if *index >= HARDENED_OFFSET {
+                write!(f, "/{}'", index - HARDENED_OFFSET)?;
+            } else {
+                write!(f, "/{}", index)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Secret key with its chain code, from which child keys can be derived.
+#[derive(Clone)]
+pub struct ExtendedSecretKey {
+    secret_key: SecretKey,
+    chain_code: [u8; 32],
+}
+
+impl std::fmt::Debug for ExtendedSecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExtendedSecretKey")
+            .field("curve", &self.secret_key.curve())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for ExtendedSecretKey {
+    fn drop(&mut self) {
+        self.chain_code.zeroize();
+    }
+}
+
+impl ExtendedSecretKey {
+    /// Master key of the given curve, from a BIP39 seed.
+    pub fn master(curve: Curve, seed: &[u8]) -> Result<Self, DerivationError> {
+        let hmac_key: &[u8] = match curve {
+            Curve::Ed25519 => b"ed25519 seed",
+            Curve::Secp256k1 => b"Bitcoin seed",
+            Curve::P256 => b"Nist256p1 seed",
+            Curve::Bls => return Err(DerivationError::UnsupportedCurve(curve)),
+        };
+
+        let mut i = hmac_sha512(hmac_key, &[seed]);
+        loop {
+            let (il, ir) = i.split_at(32);
+            if let Some(secret_key) = secret_key_of_bytes(curve, il) {
+                let mut chain_code = [0; 32];
+                chain_code.copy_from_slice(ir);
+                i.zeroize();
+                return Ok(Self {
+                    secret_key,
+                    chain_code,
+                });
+            }
+            // Invalid key: retry with I := HMAC-SHA512(key, I)
+            let next = hmac_sha512(hmac_key, &[&i]);
+            i.zeroize();
+            i = next;
+        }
+    }
+
+    /// Master key of the given curve, from a BIP39 mnemonic and passphrase.
+    pub fn from_mnemonic(
+        curve: Curve,
+        mnemonic: &str,
+        passphrase: &str,
+    ) -> Result<Self, DerivationError> {
+        let mut seed = seed_from_mnemonic(mnemonic, passphrase)?;
+        let master = Self::master(curve, &seed);
+        seed.zeroize();
+        master
+    }
+
+    /// The secret key.
+    pub fn secret_key(&self) -> &SecretKey {
+        &self.secret_key
+    }
+
+    /// The chain code.
+    pub fn chain_code(&self) -> &[u8; 32] {
+        &self.chain_code
+    }
+
+    /// Derive the child key at the given index.
+    pub fn derive_child(&self, index: u32) -> Result<Self, DerivationError> {
+        let hardened = index >= HARDENED_OFFSET;
+        let index_bytes = index.to_be_bytes();
+
+        let (curve, secret) = match &self.secret_key {
+            SecretKey::Ed25519(sk) => (Curve::Ed25519, sk.0.as_slice()),
+            SecretKey::Secp256k1(sk) => (Curve::Secp256k1, sk.0.as_slice()),
+            SecretKey::P256(sk) => (Curve::P256, sk.0.as_slice()),
+            SecretKey::Bls(_) => return Err(DerivationError::UnsupportedCurve(Curve::Bls)),
+        };
+
+        if curve == Curve::Ed25519 && !hardened {
+            return Err(DerivationError::NonHardenedIndex(index));
+        }
+
+        let mut i = if hardened {
+            hmac_sha512(&self.chain_code, &[&[0], secret, &index_bytes])
+        } else {
+            let public_key = self
+                .secret_key
+                .public_key()
+                .map_err(|_| DerivationError::UnsupportedCurve(curve))?;
+            let public_key = match &public_key {
+                crate::public_key::PublicKey::Secp256k1(pk) => pk.0.as_slice(),
+                crate::public_key::PublicKey::P256(pk) => pk.0.as_slice(),
+                _ => return Err(DerivationError::UnsupportedCurve(curve)),
+            };
+            hmac_sha512(&self.chain_code, &[public_key, &index_bytes])
+        };
+
+        loop {
+            let (il, ir) = i.split_at(32);
+            let child = match curve {
+                Curve::Ed25519 => secret_key_of_bytes(curve, il),
+                _ => add_secret_keys(curve, il, secret),
+            };
+            if let Some(secret_key) = child {
+                let mut chain_code = [0; 32];
+                chain_code.copy_from_slice(ir);
+                i.zeroize();
+                return Ok(Self {
+                    secret_key,
+                    chain_code,
+                });
+            }
+            // Invalid key: retry with I := HMAC-SHA512(c, 0x01 || IR || index)
+            let next = hmac_sha512(&self.chain_code, &[&[1], ir, &index_bytes]);
+            i.zeroize();
+            i = next;
+        }
+    }
+
+    /// Derive the key at the given path, relative to this key.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self, DerivationError> {
+        path.indices()
+            .iter()
+            .try_fold(self.clone(), |key, index| key.derive_child(*index))
+    }
+}
+
+/// Derive the secret key of a mnemonic along the given path.
+///
+/// ```
+/// use tezos_crypto_rs::derivation::derive_from_mnemonic;
+/// use tezos_crypto_rs::secret_key::Curve;
+///
+/// let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+/// let path = "m/44'/1729'/0'/0'".parse().unwrap();
+///
+/// let sk = derive_from_mnemonic(Curve::Ed25519, mnemonic, "", &path).unwrap();
+/// assert!(sk.to_b58check().starts_with("edsk"));
+/// ```
+pub fn derive_from_mnemonic(
+    curve: Curve,
+    mnemonic: &str,
+    passphrase: &str,
+    path: &DerivationPath,
+) -> Result<SecretKey, DerivationError> {
+    ExtendedSecretKey::from_mnemonic(curve, mnemonic, passphrase)?
+        .derive_path(path)
+        .map(|key| key.secret_key.clone())
+}
+
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> [u8; 64] {
+    let mut mac = Hmac::new(Sha512::new(), key);
+    for d in data {
+        mac.input(d);
+    }
+    let mut out = [0; 64];
+    mac.raw_result(&mut out);
+    out
+}
+
+// Secret key from 32 bytes, if valid for the curve.
+fn secret_key_of_bytes(curve: Curve, bytes: &[u8]) -> Option<SecretKey> {
+    match curve {
+        Curve::Ed25519 => Some(SecretKey::Ed25519(SecretKeyEd25519(bytes.to_vec()))),
+        Curve::Secp256k1 => libsecp256k1::SecretKey::parse_slice(bytes)
+            .ok()
+            .map(|_| SecretKey::Secp256k1(SecretKeySecp256k1(bytes.to_vec()))),
+        Curve::P256 => p256::SecretKey::from_bytes(bytes)
+            .ok()
+            .map(|_| SecretKey::P256(SecretKeyP256(bytes.to_vec()))),
+        Curve::Bls => None,
+    }
+}
+
+// (tweak + secret) mod n, if tweak < n and the result is non-zero.
+fn add_secret_keys(curve: Curve, tweak: &[u8], secret: &[u8]) -> Option<SecretKey> {
+    match curve {
+        Curve::Secp256k1 => {
+            let tweak = libsecp256k1::SecretKey::parse_slice(tweak).ok()?;
+            let mut child = libsecp256k1::SecretKey::parse_slice(secret).ok()?;
+            child.tweak_add_assign(&tweak).ok()?;
+            Some(SecretKey::Secp256k1(SecretKeySecp256k1(
+                child.serialize().to_vec(),
+            )))
+        }
+        Curve::P256 => {
+            use p256::elliptic_curve::group::ff::PrimeField;
+
+            let tweak = p256::Scalar::from_repr(*p256::FieldBytes::from_slice(tweak))?;
+            let secret = p256::Scalar::from_repr(*p256::FieldBytes::from_slice(secret))?;
+            let child = p256::SecretKey::from_bytes((tweak + secret).to_repr()).ok()?;
+            Some(SecretKey::P256(SecretKeyP256(child.to_bytes().to_vec())))
+        }
+        Curve::Ed25519 | Curve::Bls => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hex_sk(key: &ExtendedSecretKey) -> String {
+        match key.secret_key() {
+            SecretKey::Ed25519(sk) => hex::encode(&sk.0),
+            SecretKey::Secp256k1(sk) => hex::encode(&sk.0),
+            SecretKey::P256(sk) => hex::encode(&sk.0),
+            SecretKey::Bls(sk) => hex::encode(&sk.0),
+        }
+    }
+
+    #[test]
+    fn bip39_seed() {
+        // Test vector from https://github.com/trezor/python-mnemonic/blob/master/vectors.json
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+        let seed = seed_from_mnemonic(mnemonic, "TREZOR").unwrap();
+
+        assert_eq!(
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+            hex::encode(seed)
+        );
+
+        let seed = SeedEd25519::from_mnemonic(mnemonic, "TREZOR").unwrap();
+
+        assert_eq!(
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e5349553",
+            hex::encode(&seed.0)
+        );
+
+        assert!(matches!(
+            seed_from_mnemonic("abandon abandon abandon", ""),
+            Err(DerivationError::InvalidMnemonic(_))
+        ));
+
+        // The passphrase is NFKD-normalized: a precomposed character gives
+        // the same seed as its decomposition.
+        assert_eq!(
+            seed_from_mnemonic(mnemonic, "caf\u{e9}").unwrap(),
+            seed_from_mnemonic(mnemonic, "cafe\u{301}").unwrap()
+        );
+        assert_ne!(
+            seed_from_mnemonic(mnemonic, "caf\u{e9}").unwrap(),
+            seed_from_mnemonic(mnemonic, "cafe").unwrap()
+        );
+    }
+
+    #[test]
+    fn derivation_path() {
+        let path: DerivationPath = "m/44'/1729'/0'/0'".parse().unwrap();
+
+        assert_eq!(
+            &[
+                44 + HARDENED_OFFSET,
+                1729 + HARDENED_OFFSET,
+                HARDENED_OFFSET,
+                HARDENED_OFFSET
+            ],
+            path.indices()
+        );
+        assert_eq!("m/44'/1729'/0'/0'", path.to_string());
+
+        let path: DerivationPath = "m/0h/1/2".parse().unwrap();
+        assert_eq!("m/0'/1/2", path.to_string());
+
+        assert!("44'/1729'".parse::<DerivationPath>().is_err());
+        assert!("m/a".parse::<DerivationPath>().is_err());
+        assert!("m/2147483648".parse::<DerivationPath>().is_err());
+    }
+
+    #[test]
+    fn slip10_ed25519() {
+        // Test vector 1 from SLIP-10
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master = ExtendedSecretKey::master(Curve::Ed25519, &seed).unwrap();
+
+        assert_eq!(
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7",
+            hex_sk(&master)
+        );
+        assert_eq!(
+            "90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb",
+            hex::encode(master.chain_code())
+        );
+
+        let child = master.derive_path(&"m/0'".parse().unwrap()).unwrap();
+        assert_eq!(
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3",
+            hex_sk(&child)
+        );
+        assert_eq!(
+            "8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69",
+            hex::encode(child.chain_code())
+        );
+
+        let child = master
+            .derive_path(&"m/0'/1'/2'/2'/1000000000'".parse().unwrap())
+            .unwrap();
+        assert_eq!(
+            "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793",
+            hex_sk(&child)
+        );
+
+        assert!(matches!(
+            master.derive_child(0),
+            Err(DerivationError::NonHardenedIndex(0))
+        ));
+    }
+
+    // The default account of Temple and Kukai, derived along the Tezos BIP44
+    // path. Expected addresses were computed with an independent
+    // implementation of BIP39, SLIP-10 and ed25519, checked against the
+    // SLIP-10 test vectors.
+    #[cfg(not(feature = "mavryk"))]
+    #[test]
+    fn tezos_wallet_addresses() {
+        use crate::PublicKeyWithHash;
+
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let path = "m/44'/1729'/0'/0'".parse().unwrap();
+
+        let test_cases = [
+            (
+                "",
+                "edpku4US3ZykcZifjzSGFCmFr3zRgCKndE82estE4irj4d5oqDNDvf",
+                "tz1VQA4RP4fLjEEMW2FR4pE9kAg5abb5h5GL",
+            ),
+            (
+                "TREZOR",
+                "edpkuasvp1SFAVWmbZiKihw356x2w86B5SpeT48xDCqyiAkhdbBAu2",
+                "tz1Kg69Kr1THHqzupNnsrLZBMXqceYyNYmYh",
+            ),
+        ];
+
+        for (passphrase, expected_pk, expected_pkh) in test_cases {
+            let sk = derive_from_mnemonic(Curve::Ed25519, mnemonic, passphrase, &path).unwrap();
+            let pk = sk.public_key().unwrap();
+            assert_eq!(expected_pk, pk.to_b58check(), "{:?}", passphrase);
+            assert_eq!(expected_pkh, pk.pk_hash().to_b58check(), "{:?}", passphrase);
+        }
+    }
+
+    #[test]
+    fn bip32_secp256k1() {
+        // Test vector 1 from BIP32
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master = ExtendedSecretKey::master(Curve::Secp256k1, &seed).unwrap();
+
+        assert_eq!(
+            "e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35",
+            hex_sk(&master)
+        );
+        assert_eq!(
+            "873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508",
+            hex::encode(master.chain_code())
+        );
+
+        let test_path = |path: &str, expected_sk| {
+            let child = master.derive_path(&path.parse().unwrap()).unwrap();
+            assert_eq!(expected_sk, hex_sk(&child));
+        };
+
+        test_path(
+            "m/0'",
+            "edb2e14f9ee77d26dd93b4ecede8d16ed408ce149b6cd80b0715a2d911a0afea",
+        );
+        test_path(
+            "m/0'/1",
+            "3c6cb8d0f6a264c91ea8b5030fadaa8e538b020f0a387421a12de9319dc93368",
+        );
+        test_path(
+            "m/0'/1/2'",
+            "cbce0d719ecf7431d88e6a89fa1483e02e35092af60c042b1df2ff59fa424dca",
+        );
+        test_path(
+            "m/0'/1/2'/2",
+            "0f479245fb19a38a1954c5c7c0ebab2f9bdfd96a17563ef28a6a4b1a2a764ef4",
+        );
+        test_path(
+            "m/0'/1/2'/2/1000000000",
+            "471b76e389e528d6de6d816857e012c5455051cad6660850e58372a6c3e6e7c8",
+        );
+    }
+
+    #[test]
+    fn slip10_p256() {
+        // Test vector 1 from SLIP-10
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master = ExtendedSecretKey::master(Curve::P256, &seed).unwrap();
+
+        assert_eq!(
+            "612091aaa12e22dd2abef664f8a01a82cae99ad7441b7ef8110424915c268bc2",
+            hex_sk(&master)
+        );
+        assert_eq!(
+            "beeb672fe4621673f722f38529c07392fecaa61015c80c34f29ce8b41b3cb6ea",
+            hex::encode(master.chain_code())
+        );
+
+        let child = master.derive_path(&"m/0'".parse().unwrap()).unwrap();
+        assert_eq!(
+            "6939694369114c67917a182c59ddb8cafc3004e63ca5d3b84403ba8613debc0c",
+            hex_sk(&child)
+        );
+    }
+
+    #[test]
+    fn bls_unsupported() {
+        assert!(matches!(
+            ExtendedSecretKey::master(Curve::Bls, &[0; 32]),
+            Err(DerivationError::UnsupportedCurve(Curve::Bls))
+        ));
+    }
+}
@@ -0,0 +1,198 @@
+// SPDX-FileCopyrightText: 2024 Trilitech <contact@trili.tech>
+//
+// SPDX-License-Identifier: MIT
+
+//! Genesis of a chain, and the chains of known networks.
+//!
+//! A chain is created from a timestamp, a genesis block hash and a genesis
+//! protocol. Its chain id is derived from the genesis block hash alone, as
+//! done by [chain_id_from_block_hash], and the genesis protocol only matters
+//! to the node activating it, see [KnownNetwork::protocol].
+//!
+//! Known networks are those of Tezos, whose protocol and encodings this crate
+//! implements, with the [Network] of their addresses. Networks of other
+//! chains sharing them, such as Mavryk, are known along with their own
+//! [Network], so that they may have the same name.
+
+use crate::base58::FromBase58CheckError;
+use crate::hash::{
+    chain_id_from_block_hash, BlockHash, ChainId, ContextHash, HashTrait, Network,
+    OperationListListHash, ProtocolHash,
+};
+
+/// Parameters of the genesis of a chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Genesis {
+    /// Timestamp of the genesis block, in seconds since the Unix epoch.
+    pub time: i64,
+    /// Hash of the genesis block.
+    pub block: BlockHash,
+}
+
+impl Genesis {
+    /// Chain id of the chain.
+    pub fn chain_id(&self) -> ChainId {
+        chain_id_from_block_hash(&self.block)
+    }
+
+    /// Binary encoding of the header of the genesis block, given the hash of
+    /// its context.
+    ///
+    /// The genesis block is at level 0, with the genesis block as predecessor,
+    /// no operations and an empty fitness.
+    ///
+    /// [Genesis::block] is not the hash of this header: it is a chosen
+    /// parameter of the network, that the header refers to as predecessor,
+    /// and nodes store the genesis block under it, rather than under
+    /// [BlockHash::of_header] of the header. This is also why the chain id
+    /// only depends on [Genesis::block].
+    pub fn header(&self, context: &ContextHash) -> Vec<u8> {
+        let operations_hash = OperationListListHash::calculate(&[]);
+
+        let mut header = Vec::with_capacity(GENESIS_HEADER_SIZE);
+        // level
+        header.extend_from_slice(&0i32.to_be_bytes());
+        // proto level
+        header.push(0);
+        header.extend_from_slice(self.block.as_ref());
+        header.extend_from_slice(&self.time.to_be_bytes());
+        // validation passes
+        header.push(0);
+        header.extend_from_slice(operations_hash.as_ref());
+        // fitness, an empty list
+        header.extend_from_slice(&0u32.to_be_bytes());
+        header.extend_from_slice(context.as_ref());
+        header
+    }
+}
+
+const GENESIS_HEADER_SIZE: usize = 4 + 1 + 32 + 8 + 1 + 32 + 4 + 32;
+
+/// Genesis parameters and chain id of a known network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KnownNetwork {
+    /// Network of the chain, and of the base58check prefixes of its addresses.
+    pub network: Network,
+    /// Name of the network, such as `mainnet`, unique within [KnownNetwork::network].
+    pub name: &'static str,
+    /// Name of the chain, exchanged by nodes when connecting.
+    pub chain_name: &'static str,
+    /// Timestamp of the genesis block, in seconds since the Unix epoch.
+    pub genesis_time: i64,
+    /// Hash of the genesis block, in base58check.
+    pub genesis_block: &'static str,
+    /// Protocol of the genesis block, in base58check.
+    pub genesis_protocol: &'static str,
+    /// Chain id, in base58check.
+    pub chain_id: &'static str,
+}
+
+impl KnownNetwork {
+    /// Genesis parameters of the network.
+    pub fn genesis(&self) -> Result<Genesis, FromBase58CheckError> {
+        Ok(Genesis {
+            time: self.genesis_time,
+            block: BlockHash::from_b58check(self.genesis_block)?,
+        })
+    }
+
+    /// Protocol of the genesis block, which the node activates to start the
+    /// chain.
+    pub fn protocol(&self) -> Result<ProtocolHash, FromBase58CheckError> {
+        ProtocolHash::from_b58check(self.genesis_protocol)
+    }
+
+    /// Chain id of the network.
+    pub fn chain_id(&self) -> Result<ChainId, FromBase58CheckError> {
+        ChainId::from_b58check(self.chain_id)
+    }
+}
+
+/// Tezos mainnet.
+pub const MAINNET: KnownNetwork = KnownNetwork {
+    network: Network::Tezos,
+    name: "mainnet",
+    chain_name: "TEZOS_MAINNET",
+    // 2018-06-30T16:07:32Z
+    genesis_time: 1530374852,
+    genesis_block: "BLockGenesisGenesisGenesisGenesisGenesisf79b5d1CoW2",
+    genesis_protocol: "Ps9mPmXaRzmzk35gbAYNCAw6UXdE2qoABTHbN2oEEc1qM7CwT9P",
+    chain_id: "NetXdQprcVkpaWU",
+};
+
+/// Tezos ghostnet, the long-running testnet.
+pub const GHOSTNET: KnownNetwork = KnownNetwork {
+    network: Network::Tezos,
+    name: "ghostnet",
+    chain_name: "TEZOS_ITHACANET_2022-01-25T15:00:00Z",
+    // 2022-01-25T15:00:00Z
+    genesis_time: 1643122800,
+    genesis_block: "BLockGenesisGenesisGenesisGenesisGenesis1db77eJNeJ9",
+    genesis_protocol: "Ps9mPmXaRzmzk35gbAYNCAw6UXdE2qoABTHbN2oEEc1qM7CwT9P",
+    chain_id: "NetXnHfVqm9iesp",
+};
+
+/// Known networks.
+pub const KNOWN_NETWORKS: &[KnownNetwork] = &[MAINNET, GHOSTNET];
+
+/// Known network of the given name, among those of `network`.
+pub fn network_by_name(network: Network, name: &str) -> Option<&'static KnownNetwork> {
+    KNOWN_NETWORKS
+        .iter()
+        .find(|known| known.network == network && known.name == name)
+}
+
+/// Known network of the given chain id.
+pub fn network_by_chain_id(chain_id: &ChainId) -> Option<&'static KnownNetwork> {
+    KNOWN_NETWORKS
+        .iter()
+        .find(|network| matches!(network.chain_id(), Ok(id) if id == *chain_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_networks() {
+        for network in KNOWN_NETWORKS {
+            let genesis = network.genesis().unwrap();
+            let chain_id = network.chain_id().unwrap();
+            assert!(network.protocol().is_ok(), "{}", network.name);
+            assert_eq!(genesis.chain_id(), chain_id, "{}", network.name);
+            assert_eq!(
+                network_by_name(network.network, network.name),
+                Some(network)
+            );
+            assert_eq!(network_by_chain_id(&chain_id), Some(network));
+        }
+
+        assert_eq!(network_by_name(Network::Tezos, "mainnet"), Some(&MAINNET));
+        assert_eq!(network_by_name(Network::Mavryk, "mainnet"), None);
+        assert_eq!(network_by_name(Network::Tezos, "sandbox"), None);
+        let chain_id = ChainId::from_b58check("NetXgtSLGNJvNye").unwrap();
+        assert_eq!(network_by_chain_id(&chain_id), None);
+    }
+
+    #[test]
+    fn genesis_header() {
+        let genesis = Genesis {
+            time: 1530374852,
+            block: BlockHash::from_b58check("BLockGenesisGenesisGenesisGenesisGenesisb83baZgbyZe")
+                .unwrap(),
+        };
+        assert_eq!(genesis.chain_id().to_b58check(), "NetXgtSLGNJvNye");
+
+        let context = ContextHash::try_from_bytes(&[7; 32]).unwrap();
+        let header = genesis.header(&context);
+        assert_eq!(header.len(), GENESIS_HEADER_SIZE);
+        assert_eq!(header[..5], [0; 5]);
+        assert_eq!(header[5..37], *genesis.block.as_ref());
+        assert_eq!(header[37..45], 1530374852i64.to_be_bytes());
+        assert_eq!(header[45], 0);
+        assert_eq!(header[46..78], crate::blake2b::digest_256(&[]));
+        assert_eq!(header[78..82], [0; 4]);
+        assert_eq!(header[82..], [7; 32]);
+        assert_ne!(BlockHash::of_header(&header), genesis.block);
+    }
+}
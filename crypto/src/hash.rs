@@ -5,8 +5,9 @@
 use std::convert::{TryFrom, TryInto};
 
 use crate::{
-    base58::{FromBase58Check, FromBase58CheckError, ToBase58Check},
+    base58::{write_base58check, FromBase58Check, FromBase58CheckError, ToBase58Check},
     blake2b::{self, Blake2bError},
+    watermark::Watermark,
     CryptoError, PublicKeySignatureVerifier, PublicKeyWithHash,
 };
 use serde::{Deserialize, Serialize};
@@ -14,6 +15,7 @@ use thiserror::Error;
 use zeroize::Zeroize;
 
 mod encoding;
+mod prefixed;
 
 const CRYPTO_KEY_SIZE: usize = 32;
 
@@ -34,6 +36,10 @@ mod prefix_bytes {
     pub const CONTRACT_TZ2_HASH: [u8; 3] = [6, 161, 161];
     pub const CONTRACT_TZ3_HASH: [u8; 3] = [6, 161, 164];
     pub const CONTRACT_TZ4_HASH: [u8; 3] = [6, 161, 166];
+    pub const CONTRACT_MV1_HASH: [u8; 3] = [5, 186, 196];
+    pub const CONTRACT_MV2_HASH: [u8; 3] = [5, 186, 199];
+    pub const CONTRACT_MV3_HASH: [u8; 3] = [5, 186, 201];
+    pub const CONTRACT_MV4_HASH: [u8; 3] = [5, 186, 204];
     pub const PUBLIC_KEY_ED25519: [u8; 4] = [13, 15, 37, 217];
     pub const PUBLIC_KEY_SECP256K1: [u8; 4] = [3, 254, 226, 86];
     pub const PUBLIC_KEY_P256: [u8; 4] = [3, 178, 139, 127];
@@ -42,34 +48,132 @@ mod prefix_bytes {
     // SecretKeyEd25519 uses identical b58 encoding as SeedEd25519 in
     // non-legacy format.
     pub const SECRET_KEY_ED25519: [u8; 4] = SEED_ED25519;
+    pub const SECRET_KEY_SECP256K1: [u8; 4] = [17, 162, 224, 201];
+    pub const SECRET_KEY_P256: [u8; 4] = [16, 81, 238, 189];
     pub const SECRET_KEY_BLS: [u8; 4] = [3, 150, 192, 40];
+    /// Prefix of BLS secret key shares, `BLssk`.
+    ///
+    /// Crate-local: octez has no threshold BLS, so this prefix is not
+    /// recognised by octez tools.
+    pub const SECRET_KEY_BLS_SHARE: [u8; 4] = [10, 175, 74, 52];
+    pub const ENCRYPTED_SECRET_KEY_ED25519: [u8; 5] = [7, 90, 60, 179, 41];
+    pub const ENCRYPTED_SECRET_KEY_SECP256K1: [u8; 5] = [9, 237, 241, 174, 150];
+    pub const ENCRYPTED_SECRET_KEY_P256: [u8; 5] = [9, 48, 57, 115, 171];
+    pub const ENCRYPTED_SECRET_KEY_BLS: [u8; 5] = [2, 5, 30, 53, 25];
     pub const GENERIC_SIGNATURE_HASH: [u8; 3] = [4, 130, 43];
     pub const ED22519_SIGNATURE_HASH: [u8; 5] = [9, 245, 205, 134, 18];
     pub const SECP256K1_SIGNATURE_HASH: [u8; 5] = [13, 115, 101, 19, 63];
     pub const P256_SIGNATURE_HASH: [u8; 4] = [54, 240, 44, 52];
     pub const BLS_SIGNATURE_HASH: [u8; 4] = [40, 171, 64, 207];
+    // Proofs of possession are encoded as BLS signatures.
+    pub const BLS_PROOF_OF_POSSESSION: [u8; 4] = BLS_SIGNATURE_HASH;
+    /// Prefix of partial BLS signatures of a share, `BLpsig`.
+    ///
+    /// Crate-local, as [SECRET_KEY_BLS_SHARE].
+    pub const BLS_PARTIAL_SIGNATURE: [u8; 5] = [27, 109, 250, 220, 31];
     pub const NONCE_HASH: [u8; 3] = [69, 220, 169];
     pub const OPERATION_LIST_HASH: [u8; 2] = [133, 233];
     pub const SMART_ROLLUP_HASH: [u8; 3] = [6, 124, 117];
+    pub const SMART_ROLLUP_COMMITMENT_HASH: [u8; 4] = [17, 165, 134, 138];
+    pub const SMART_ROLLUP_STATE_HASH: [u8; 4] = [17, 165, 235, 240];
+    pub const SMART_ROLLUP_INBOX_HASH: [u8; 5] = [3, 255, 138, 145, 110];
+    pub const SMART_ROLLUP_MERKELIZED_PAYLOAD_HASHES_HASH: [u8; 5] = [3, 255, 138, 145, 140];
+    pub const SCRIPT_EXPR_HASH: [u8; 4] = [13, 44, 64, 27];
+    pub const BLINDED_PUBLIC_KEY_HASH: [u8; 4] = [1, 2, 49, 223];
+    pub const DAL_COMMITMENT: [u8; 3] = [2, 116, 180];
 }
 
 pub type Hash = Vec<u8>;
 
+/// Hash types, with their base58check encodings.
+///
+/// Implemented by the hash types of this crate, and by those defined with
+/// [define_prefixed_hash](crate::define_prefixed_hash).
 pub trait HashTrait: Into<Hash> + AsRef<[u8]> {
-    /// Returns this hash type.
-    fn hash_type() -> HashType;
-
     /// Returns the size of this hash.
-    fn hash_size() -> usize {
-        Self::hash_type().size()
-    }
+    fn hash_size() -> usize;
+
+    /// Base58check prefix of this hash, for the given network.
+    fn base58check_prefix_for(network: Network) -> &'static [u8];
 
     /// Tries to create this hash from the `bytes`.
     fn try_from_bytes(bytes: &[u8]) -> Result<Self, FromBytesError>;
 
-    fn from_b58check(data: &str) -> Result<Self, FromBase58CheckError>;
+    /// Creates this hash from its base58check encoding, with the prefix of
+    /// any network.
+    fn from_b58check(data: &str) -> Result<Self, FromBase58CheckError> {
+        for network in Network::ALL {
+            match Self::from_b58check_for(network, data) {
+                Err(FromBase58CheckError::IncorrectBase58Prefix) => continue,
+                result => return result,
+            }
+        }
+        Err(FromBase58CheckError::IncorrectBase58Prefix)
+    }
+
+    fn to_b58check(&self) -> String {
+        self.to_b58check_for(Network::DEFAULT)
+    }
+
+    /// Creates this hash from its base58check encoding for the given
+    /// network only.
+    fn from_b58check_for(network: Network, data: &str) -> Result<Self, FromBase58CheckError> {
+        let prefix = Self::base58check_prefix_for(network);
+        let bytes = crate::base58::from_base58check_with_prefix(data, prefix, Self::hash_size())?;
+        Self::try_from_bytes(&bytes).map_err(|_| FromBase58CheckError::InvalidBase58)
+    }
+
+    /// Base58check encoding of this hash for the given network.
+    fn to_b58check_for(&self, network: Network) -> String {
+        let mut encoded = String::new();
+        self.write_b58check_for(network, &mut encoded)
+            .expect("base58check encoding into a String does not fail");
+        encoded
+    }
+
+    /// Writes the base58check encoding of this hash for the given network,
+    /// without allocating.
+    fn write_b58check_for<W>(&self, network: Network, out: &mut W) -> std::fmt::Result
+    where
+        W: std::fmt::Write + ?Sized,
+    {
+        write_base58check(Self::base58check_prefix_for(network), self.as_ref(), out)
+    }
+}
 
-    fn to_b58check(&self) -> String;
+/// Network profile, for the base58check prefixes of addresses.
+///
+/// Mavryk implicit accounts are `mv1`, `mv2`, `mv3` and `mv4`, instead of
+/// `tz1`, `tz2`, `tz3` and `tz4`. Other prefixes, such as `KT1` for
+/// originated contracts, are the same for both networks.
+///
+/// Decoding from base58check accepts the prefixes of every network, while
+/// encoding uses [Network::DEFAULT], unless a network is given, see
+/// [HashTrait::to_b58check_for]. This includes `Display` and serde, so that
+/// with the `mavryk` feature, `mv1` addresses round-trip as such.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Network {
+    Tezos,
+    Mavryk,
+}
+
+impl Network {
+    /// Network used for encoding, when none is given: [Network::Mavryk] with
+    /// the `mavryk` feature, [Network::Tezos] otherwise.
+    #[cfg(not(feature = "mavryk"))]
+    pub const DEFAULT: Network = Network::Tezos;
+    /// Network used for encoding, when none is given: [Network::Mavryk] with
+    /// the `mavryk` feature, [Network::Tezos] otherwise.
+    #[cfg(feature = "mavryk")]
+    pub const DEFAULT: Network = Network::Mavryk;
+
+    pub const ALL: [Network; 2] = [Network::Tezos, Network::Mavryk];
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
 }
 
 /// Error creating hash from bytes
@@ -85,22 +189,120 @@ pub enum FromBytesError {
 }
 
 macro_rules! define_hash {
+    ($(#[$meta:meta])* $name:ident) => {
+        $crate::define_prefixed_hash!(
+            @define
+            $(#[$meta])*
+            pub $name(pub(crate)),
+            prefix: |network| HashType::$name.base58check_prefix_for(network),
+            size: HashType::$name.size(),
+        );
+
+        impl $name {
+            /// Returns this hash type.
+            pub fn hash_type() -> HashType {
+                HashType::$name
+            }
+
+            pub fn from_base58_check(data: &str) -> Result<Self, FromBase58CheckError> {
+                Self::from_b58check(data)
+            }
+
+            pub fn to_base58_check(&self) -> String {
+                self.to_b58check()
+            }
+        }
+
+        #[cfg(feature = "fuzzing")]
+        impl fuzzcheck::DefaultMutator for $name {
+            type Mutator = fuzzcheck::mutators::unit::UnitMutator<$name>;
+            #[no_coverage]
+            fn default_mutator() -> Self::Mutator {
+                fuzzcheck::mutators::unit::UnitMutator::new($name(
+                    [0u8; HashType::$name.size()].into(),
+                ))
+            }
+        }
+    };
+}
+
+/// Wrapper opting in to serde (de)serialization of secret keys.
+///
+/// Secret keys do not implement [Serialize] and [Deserialize], so that they
+/// cannot be serialized by accident, as part of a larger structure. Wrap them
+/// explicitly to serialize them as base58check, or as bytes for binary
+/// formats.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExposedSecret<T>(pub T);
+
+// Visitor of secret keys, from base58check or bytes.
+struct SecretVisitor<T>(std::marker::PhantomData<T>);
+
+impl<'de, T: HashTrait> serde::de::Visitor<'de> for SecretVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("either sequence of bytes or base58 encoded data expected")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        T::from_b58check(v).map_err(|e| {
+            E::custom(format!(
+                "error constructing secret key from base58check: {}",
+                e
+            ))
+        })
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut bytes = <Vec<u8> as Deserialize>::deserialize(deserializer)?;
+        let secret = T::try_from_bytes(&bytes);
+        bytes.zeroize();
+        secret.map_err(|e| {
+            serde::de::Error::custom(format!("error constructing secret key from bytes: {}", e))
+        })
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let mut bytes = seq
+            .next_element::<Vec<u8>>()?
+            .ok_or_else(|| serde::de::Error::custom("no secret key bytes"))?;
+        let secret = T::try_from_bytes(&bytes);
+        bytes.zeroize();
+        secret.map_err(|e| {
+            serde::de::Error::custom(format!("error constructing secret key from bytes: {}", e))
+        })
+    }
+}
+
+/// Like `define_hash`, for secret material.
+///
+/// The bytes are zeroized on drop, compared in constant time, and never
+/// printed by `Debug` or `Display`. Serde is only available through
+/// [ExposedSecret].
+macro_rules! define_secret_key {
     ($name:ident) => {
-        #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[derive(Clone)]
         pub struct $name(pub(crate) Hash);
 
         impl $name {
-            fn from_bytes(data: &[u8]) -> Result<Self, FromBytesError> {
-                if data.len() == HashType::$name.size() {
-                    Ok($name(data.into()))
-                } else {
-                    Err(FromBytesError::InvalidSize)
-                }
+            /// Returns this hash type.
+            pub fn hash_type() -> HashType {
+                HashType::$name
             }
 
-            fn from_vec(hash: Vec<u8>) -> Result<Self, FromBytesError> {
-                if hash.len() == HashType::$name.size() {
-                    Ok($name(hash))
+            fn from_bytes(data: &[u8]) -> Result<Self, FromBytesError> {
+                if data.len() == HashType::$name.size() {
+                    Ok($name(data.to_vec()))
                 } else {
                     Err(FromBytesError::InvalidSize)
                 }
@@ -115,14 +317,6 @@ macro_rules! define_hash {
             }
         }
 
-        impl ::std::fmt::Display for $name {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                // TODO - TE-373: with b58 this could be done without the need
-                // to perform a heap allocation.
-                write!(f, "{}", self.to_base58_check())
-            }
-        }
-
         #[cfg(feature = "fuzzing")]
         impl fuzzcheck::DefaultMutator for $name {
             type Mutator = fuzzcheck::mutators::unit::UnitMutator<$name>;
@@ -134,16 +328,6 @@ macro_rules! define_hash {
             }
         }
 
-        impl ::std::fmt::Debug for $name {
-            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
-                // TODO - TE-373: with b58 this could be done without the need
-                // to perform a heap allocation.
-                f.debug_tuple(stringify!($name))
-                    .field(&self.to_base58_check())
-                    .finish()
-            }
-        }
-
         impl std::str::FromStr for $name {
             type Err = FromBase58CheckError;
 
@@ -153,25 +337,26 @@ macro_rules! define_hash {
         }
 
         impl HashTrait for $name {
-            fn hash_type() -> HashType {
-                HashType::$name
+            fn hash_size() -> usize {
+                HashType::$name.size()
             }
 
-            fn try_from_bytes(bytes: &[u8]) -> Result<Self, FromBytesError> {
-                $name::try_from(bytes)
+            fn base58check_prefix_for(network: Network) -> &'static [u8] {
+                HashType::$name.base58check_prefix_for(network)
             }
 
-            fn from_b58check(data: &str) -> Result<Self, FromBase58CheckError> {
-                HashType::$name.b58check_to_hash(data).map(Self)
+            fn try_from_bytes(bytes: &[u8]) -> Result<Self, FromBytesError> {
+                Self::from_bytes(bytes)
             }
 
-            fn to_b58check(&self) -> String {
-                // TODO: Fixing TE-373 will allow to get rid of this `unreachable`
-                HashType::$name
-                    .hash_to_b58check(&self.0)
-                    .unwrap_or_else(|_| {
-                        unreachable!("Typed hash should always be representable in base58")
-                    })
+            // Moves the decoded bytes, rather than leaving a copy behind.
+            fn from_b58check_for(
+                network: Network,
+                data: &str,
+            ) -> Result<Self, FromBase58CheckError> {
+                let prefix = Self::base58check_prefix_for(network);
+                crate::base58::from_base58check_with_prefix(data, prefix, Self::hash_size())
+                    .map($name)
             }
         }
 
@@ -181,12 +366,6 @@ macro_rules! define_hash {
             }
         }
 
-        impl std::convert::From<$name> for Hash {
-            fn from(typed_hash: $name) -> Self {
-                typed_hash.0
-            }
-        }
-
         impl std::convert::TryFrom<&[u8]> for $name {
             type Error = FromBytesError;
             fn try_from(h: &[u8]) -> Result<Self, Self::Error> {
@@ -196,8 +375,13 @@ macro_rules! define_hash {
 
         impl std::convert::TryFrom<Hash> for $name {
             type Error = FromBytesError;
-            fn try_from(h: Hash) -> Result<Self, Self::Error> {
-                Self::from_vec(h)
+            fn try_from(mut h: Hash) -> Result<Self, Self::Error> {
+                if h.len() == HashType::$name.size() {
+                    Ok($name(h))
+                } else {
+                    h.zeroize();
+                    Err(FromBytesError::InvalidSize)
+                }
             }
         }
 
@@ -208,81 +392,67 @@ macro_rules! define_hash {
             }
         }
 
-        impl Serialize for $name {
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("<redacted>")
+            }
+        }
+
+        impl ::std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_tuple(stringify!($name))
+                    .field(&format_args!("<redacted>"))
+                    .finish()
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                subtle::ConstantTimeEq::ct_eq(self.0.as_slice(), other.0.as_slice()).into()
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                self.0.zeroize();
+            }
+        }
+
+        impl zeroize::ZeroizeOnDrop for $name {}
+
+        impl std::convert::From<$name> for Hash {
+            fn from(mut secret: $name) -> Self {
+                std::mem::take(&mut secret.0)
+            }
+        }
+
+        impl Serialize for ExposedSecret<$name> {
             fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
             where
                 S: serde::Serializer,
             {
                 if serializer.is_human_readable() {
-                    serializer.serialize_str(&self.to_base58_check())
+                    serializer.serialize_str(&self.0.to_base58_check())
                 } else {
-                    serializer.serialize_newtype_struct(stringify!($name), &self.0)
+                    serializer.serialize_newtype_struct(stringify!($name), &self.0 .0)
                 }
             }
         }
 
-        impl<'de> Deserialize<'de> for $name {
+        impl<'de> Deserialize<'de> for ExposedSecret<$name> {
             fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
             where
                 D: serde::de::Deserializer<'de>,
             {
-                struct HashVisitor;
-
-                impl<'de> serde::de::Visitor<'de> for HashVisitor {
-                    type Value = $name;
-
-                    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                        formatter
-                            .write_str("eigher sequence of bytes or base58 encoded data expected")
-                    }
-
-                    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
-                    where
-                        E: serde::de::Error,
-                    {
-                        Self::Value::from_b58check(v).map_err(|e| {
-                            E::custom(format!("error constructing hash from base58check: {}", e))
-                        })
-                    }
-
-                    fn visit_newtype_struct<E>(self, e: E) -> Result<Self::Value, E::Error>
-                    where
-                        E: serde::Deserializer<'de>,
-                    {
-                        let field0: Vec<u8> = match <Vec<u8> as serde::Deserialize>::deserialize(e)
-                        {
-                            Ok(val) => val,
-                            Err(err) => {
-                                return Err(err);
-                            }
-                        };
-                        Ok($name(field0))
-                    }
-
-                    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
-                    where
-                        A: serde::de::SeqAccess<'de>,
-                    {
-                        let hash = match seq.next_element::<Vec<u8>>() {
-                            Ok(Some(val)) => val,
-                            Ok(None) => {
-                                return Err(serde::de::Error::custom("no hash bytes".to_string()))
-                            }
-                            Err(err) => return Err(err),
-                        };
-                        Self::Value::try_from_bytes(&hash).map_err(|e| {
-                            serde::de::Error::custom(format!(
-                                "error constructing hash from bytes: {}",
-                                e
-                            ))
-                        })
-                    }
-                }
-
+                let visitor = SecretVisitor(std::marker::PhantomData);
                 if deserializer.is_human_readable() {
-                    deserializer.deserialize_str(HashVisitor)
+                    deserializer.deserialize_str(visitor).map(ExposedSecret)
                 } else {
-                    deserializer.deserialize_newtype_struct(stringify!($name), HashVisitor)
+                    deserializer
+                        .deserialize_newtype_struct(stringify!($name), visitor)
+                        .map(ExposedSecret)
                 }
             }
         }
@@ -309,17 +479,54 @@ define_hash!(PublicKeyEd25519);
 define_hash!(PublicKeySecp256k1);
 define_hash!(PublicKeyP256);
 define_hash!(PublicKeyBls);
-define_hash!(SeedEd25519);
-define_hash!(SecretKeyEd25519);
-define_hash!(SecretKeyBls);
+define_secret_key!(SeedEd25519);
+define_secret_key!(SecretKeyEd25519);
+define_secret_key!(SecretKeySecp256k1);
+define_secret_key!(SecretKeyP256);
+define_secret_key!(SecretKeyBls);
+define_secret_key!(SecretKeyBlsShare);
+define_hash!(EncryptedSecretKeyEd25519);
+define_hash!(EncryptedSecretKeySecp256k1);
+define_hash!(EncryptedSecretKeyP256);
+define_hash!(EncryptedSecretKeyBls);
 define_hash!(UnknownSignature);
 define_hash!(Ed25519Signature);
 define_hash!(Secp256k1Signature);
 define_hash!(P256Signature);
-define_hash!(BlsSignature);
+define_hash!(
+    /// BLS signature, in the Aug scheme of octez.
+    ///
+    /// Its base58check prefix `BLsig` is shared with [BlsProofOfPossession]:
+    /// a `BLsig` string is always detected as a signature by [AnyHash].
+    BlsSignature
+);
+define_hash!(
+    /// BLS proof of possession of a secret key, as in the reveal of a tz4.
+    ///
+    /// Octez encodes proofs of possession as BLS signatures, so this type
+    /// shares the `BLsig` prefix and size of [BlsSignature]: the two cannot
+    /// be told apart from their encodings, and [AnyHash] always detects a
+    /// `BLsig` string as a [BlsSignature].
+    BlsProofOfPossession
+);
+define_hash!(BlsPartialSignature);
 define_hash!(NonceHash);
 define_hash!(OperationListHash);
 define_hash!(SmartRollupHash);
+define_hash!(SmartRollupCommitmentHash);
+define_hash!(SmartRollupStateHash);
+define_hash!(SmartRollupInboxHash);
+define_hash!(SmartRollupMerkelizedPayloadHashesHash);
+define_hash!(ScriptExprHash);
+define_hash!(BlindedPublicKeyHash);
+define_hash!(
+    /// Commitment to the content of a DAL slot, published in its slot header.
+    ///
+    /// The `sh` prefix is the `slot_header` prefix of octez: the commitment
+    /// is what identifies a slot header, and there is no separate hash kind
+    /// for slot headers.
+    DalCommitment
+);
 
 macro_rules! unknown_sig {
     ($sig:ident) => {
@@ -369,13 +576,13 @@ pub enum HashType {
     CryptoboxPublicKeyHash,
     // "\002\090\121" (* KT1(36) *)
     ContractKt1Hash,
-    // "\006\161\159" (* tz1(36) *)
+    // "\006\161\159" (* tz1(36) *), "\005\186\196" (* mv1(36) *)
     ContractTz1Hash,
-    // "\006\161\161" (* tz2(36) *)
+    // "\006\161\161" (* tz2(36) *), "\005\186\199" (* mv2(36) *)
     ContractTz2Hash,
-    // "\006\161\164" (* tz3(36) *)
+    // "\006\161\164" (* tz3(36) *), "\005\186\201" (* mv3(36) *)
     ContractTz3Hash,
-    // "\006\161\166" (* tz4(36) *)
+    // "\006\161\166" (* tz4(36) *), "\005\186\204" (* mv4(36) *)
     ContractTz4Hash,
     // "\013\015\037\217" (* edpk(54) *)
     PublicKeyEd25519,
@@ -389,8 +596,22 @@ pub enum HashType {
     SeedEd25519,
     // "\013\015\058\007" (* edsk(54) *)
     SecretKeyEd25519,
+    // "\017\162\224\201" (* spsk(54) *)
+    SecretKeySecp256k1,
+    // "\016\081\238\189" (* p2sk(54) *)
+    SecretKeyP256,
     // "\003\150\192\040" (* BLsk(54) *)
     SecretKeyBls,
+    // "\010\175\074\052" (* BLssk(57) *), not in octez
+    SecretKeyBlsShare,
+    // "\007\090\060\179\041" (* edesk(88) *)
+    EncryptedSecretKeyEd25519,
+    // "\009\237\241\174\150" (* spesk(88) *)
+    EncryptedSecretKeySecp256k1,
+    // "\009\048\057\115\171" (* p2esk(88) *)
+    EncryptedSecretKeyP256,
+    // "\002\005\030\053\025" (* BLesk(88) *)
+    EncryptedSecretKeyBls,
     // "\004\130\043" (* sig(96) *)
     UnknownSignature,
     // "\009\245\205\134\018" (* edsig(99) *)
@@ -401,18 +622,50 @@ pub enum HashType {
     P256Signature,
     // "\040\171\064\207" (* BLsig(142) *)
     BlsSignature,
+    // "\040\171\064\207" (* BLsig(142) *)
+    BlsProofOfPossession,
+    // "\027\109\250\220\031" (* BLpsig(146) *), not in octez
+    BlsPartialSignature,
     // "\069\220\169" (* nce(53) *)
     NonceHash,
     // "\133\233" (* Lo(52) *)
     OperationListHash,
     // "\006\124\117" (* sr1(36) *)
     SmartRollupHash,
+    // "\017\165\134\138" (* src1(54) *)
+    SmartRollupCommitmentHash,
+    // "\017\165\235\240" (* srs1(54) *)
+    SmartRollupStateHash,
+    // "\003\255\138\145\110" (* srib1(55) *)
+    SmartRollupInboxHash,
+    // "\003\255\138\145\140" (* srib2(55) *)
+    SmartRollupMerkelizedPayloadHashesHash,
+    // "\013\044\064\027" (* expr(54) *)
+    ScriptExprHash,
+    // "\001\002\049\223" (* btz1(37) *)
+    BlindedPublicKeyHash,
+    // "\002\116\180" (* sh(74) *), slot_header in octez
+    DalCommitment,
 }
 
 impl HashType {
+    /// Base58check prefix of this hash type, for [Network::DEFAULT].
     #[inline]
     pub fn base58check_prefix(&self) -> &'static [u8] {
+        self.base58check_prefix_for(Network::DEFAULT)
+    }
+
+    /// Base58check prefix of this hash type, for the given network.
+    #[inline]
+    pub fn base58check_prefix_for(&self, network: Network) -> &'static [u8] {
         use prefix_bytes::*;
+        match (self, network) {
+            (HashType::ContractTz1Hash, Network::Mavryk) => return &CONTRACT_MV1_HASH,
+            (HashType::ContractTz2Hash, Network::Mavryk) => return &CONTRACT_MV2_HASH,
+            (HashType::ContractTz3Hash, Network::Mavryk) => return &CONTRACT_MV3_HASH,
+            (HashType::ContractTz4Hash, Network::Mavryk) => return &CONTRACT_MV4_HASH,
+            _ => (),
+        }
         match self {
             HashType::ChainId => &CHAIN_ID,
             HashType::BlockHash => &BLOCK_HASH,
@@ -436,15 +689,33 @@ impl HashType {
             HashType::PublicKeyBls => &PUBLIC_KEY_BLS,
             HashType::SeedEd25519 => &SEED_ED25519,
             HashType::SecretKeyEd25519 => &SECRET_KEY_ED25519,
+            HashType::SecretKeySecp256k1 => &SECRET_KEY_SECP256K1,
+            HashType::SecretKeyP256 => &SECRET_KEY_P256,
             HashType::SecretKeyBls => &SECRET_KEY_BLS,
+            HashType::SecretKeyBlsShare => &SECRET_KEY_BLS_SHARE,
+            HashType::EncryptedSecretKeyEd25519 => &ENCRYPTED_SECRET_KEY_ED25519,
+            HashType::EncryptedSecretKeySecp256k1 => &ENCRYPTED_SECRET_KEY_SECP256K1,
+            HashType::EncryptedSecretKeyP256 => &ENCRYPTED_SECRET_KEY_P256,
+            HashType::EncryptedSecretKeyBls => &ENCRYPTED_SECRET_KEY_BLS,
             HashType::UnknownSignature => &GENERIC_SIGNATURE_HASH,
             HashType::Ed25519Signature => &ED22519_SIGNATURE_HASH,
             HashType::Secp256k1Signature => &SECP256K1_SIGNATURE_HASH,
             HashType::P256Signature => &P256_SIGNATURE_HASH,
             HashType::BlsSignature => &BLS_SIGNATURE_HASH,
+            HashType::BlsProofOfPossession => &BLS_PROOF_OF_POSSESSION,
+            HashType::BlsPartialSignature => &BLS_PARTIAL_SIGNATURE,
             HashType::NonceHash => &NONCE_HASH,
             HashType::OperationListHash => &OPERATION_LIST_HASH,
             HashType::SmartRollupHash => &SMART_ROLLUP_HASH,
+            HashType::SmartRollupCommitmentHash => &SMART_ROLLUP_COMMITMENT_HASH,
+            HashType::SmartRollupStateHash => &SMART_ROLLUP_STATE_HASH,
+            HashType::SmartRollupInboxHash => &SMART_ROLLUP_INBOX_HASH,
+            HashType::SmartRollupMerkelizedPayloadHashesHash => {
+                &SMART_ROLLUP_MERKELIZED_PAYLOAD_HASHES_HASH
+            }
+            HashType::ScriptExprHash => &SCRIPT_EXPR_HASH,
+            HashType::BlindedPublicKeyHash => &BLINDED_PUBLIC_KEY_HASH,
+            HashType::DalCommitment => &DAL_COMMITMENT,
         }
     }
 
@@ -463,48 +734,129 @@ impl HashType {
             | HashType::OperationMetadataListListHash
             | HashType::PublicKeyEd25519
             | HashType::NonceHash
-            | HashType::OperationListHash => 32,
+            | HashType::OperationListHash
+            | HashType::SmartRollupCommitmentHash
+            | HashType::SmartRollupStateHash
+            | HashType::SmartRollupInboxHash
+            | HashType::SmartRollupMerkelizedPayloadHashesHash
+            | HashType::ScriptExprHash => 32,
             HashType::CryptoboxPublicKeyHash => 16,
             HashType::ContractKt1Hash
             | HashType::ContractTz1Hash
             | HashType::ContractTz2Hash
             | HashType::ContractTz3Hash
             | HashType::ContractTz4Hash
-            | HashType::SmartRollupHash => 20,
+            | HashType::SmartRollupHash
+            | HashType::BlindedPublicKeyHash => 20,
             HashType::PublicKeySecp256k1 | HashType::PublicKeyP256 => 33,
-            HashType::SecretKeyEd25519 | HashType::SeedEd25519 | HashType::SecretKeyBls => 32,
-            HashType::PublicKeyBls => 48,
+            HashType::SecretKeyEd25519
+            | HashType::SeedEd25519
+            | HashType::SecretKeySecp256k1
+            | HashType::SecretKeyP256
+            | HashType::SecretKeyBls => 32,
+            // salt (8 bytes) + MAC (16 bytes) + encrypted secret key (32 bytes)
+            HashType::EncryptedSecretKeyEd25519
+            | HashType::EncryptedSecretKeySecp256k1
+            | HashType::EncryptedSecretKeyP256
+            | HashType::EncryptedSecretKeyBls => 56,
+            HashType::PublicKeyBls | HashType::DalCommitment => 48,
             HashType::Ed25519Signature
             | HashType::Secp256k1Signature
             | HashType::P256Signature
             | HashType::UnknownSignature => 64,
-            HashType::BlsSignature => 96,
+            HashType::BlsSignature | HashType::BlsProofOfPossession => 96,
+            // index (2 bytes) + secret key (32 bytes)
+            HashType::SecretKeyBlsShare => 34,
+            // index (2 bytes) + signature (96 bytes)
+            HashType::BlsPartialSignature => 98,
         }
     }
 
     /// Convert hash byte representation into string.
     pub fn hash_to_b58check(&self, data: &[u8]) -> Result<String, FromBytesError> {
+        self.hash_to_b58check_for(Network::DEFAULT, data)
+    }
+
+    /// Convert hash byte representation into string, for the given network.
+    pub fn hash_to_b58check_for(
+        &self,
+        network: Network,
+        data: &[u8],
+    ) -> Result<String, FromBytesError> {
         if self.size() != data.len() {
             Err(FromBytesError::InvalidSize)
         } else {
-            let mut hash = Vec::with_capacity(self.base58check_prefix().len() + data.len());
+            let prefix = self.base58check_prefix_for(network);
+            let mut hash = Vec::with_capacity(prefix.len() + data.len());
 
-            hash.extend(self.base58check_prefix());
+            hash.extend(prefix);
             hash.extend(data);
 
             Ok(hash.to_base58check())
         }
     }
 
-    /// Convert string representation of the hash to bytes form.
+    /// Convert string representation of the hash to bytes form, with the
+    /// prefix of any network.
     pub fn b58check_to_hash(&self, data: &str) -> Result<Hash, FromBase58CheckError> {
-        let mut hash = data.from_base58check()?;
+        let hash = data.from_base58check()?;
 
-        if !hash.starts_with(self.base58check_prefix()) {
+        let prefix = Network::ALL
+            .iter()
+            .map(|network| self.base58check_prefix_for(*network))
+            .find(|prefix| hash.starts_with(prefix))
+            .ok_or(FromBase58CheckError::IncorrectBase58Prefix)?;
+
+        self.strip_prefix(prefix, hash)
+    }
+
+    /// Convert string representation of the hash to bytes form, with the
+    /// prefix of the given network only.
+    pub fn b58check_to_hash_for(
+        &self,
+        network: Network,
+        data: &str,
+    ) -> Result<Hash, FromBase58CheckError> {
+        let hash = data.from_base58check()?;
+
+        let prefix = self.base58check_prefix_for(network);
+        if !hash.starts_with(prefix) {
             return Err(FromBase58CheckError::IncorrectBase58Prefix);
         }
 
-        let expected_len = self.size() + self.base58check_prefix().len();
+        self.strip_prefix(prefix, hash)
+    }
+
+    /// Detects the hash type of a base58check encoded string, from its
+    /// prefix, for any network, and its length.
+    ///
+    /// Fails with [FromBase58CheckError::UnknownBase58Prefix] if no hash type
+    /// has the prefix, and with [FromBase58CheckError::MismatchedLength] if
+    /// the length does not match. See [AnyHash] for the typed value.
+    pub fn detect(data: &str) -> Result<HashType, FromBase58CheckError> {
+        Self::detect_hash(data).map(|(hash_type, _)| hash_type)
+    }
+
+    fn detect_hash(data: &str) -> Result<(HashType, Hash), FromBase58CheckError> {
+        let hash = data.from_base58check()?;
+
+        let mut error = FromBase58CheckError::UnknownBase58Prefix;
+        for hash_type in HashType::ALL {
+            for network in Network::ALL {
+                let prefix = hash_type.base58check_prefix_for(network);
+                if hash.starts_with(prefix) {
+                    match hash_type.strip_prefix(prefix, hash.clone()) {
+                        Ok(hash) => return Ok((*hash_type, hash)),
+                        Err(e) => error = e,
+                    }
+                }
+            }
+        }
+        Err(error)
+    }
+
+    fn strip_prefix(&self, prefix: &[u8], mut hash: Vec<u8>) -> Result<Hash, FromBase58CheckError> {
+        let expected_len = self.size() + prefix.len();
         if expected_len != hash.len() {
             return Err(FromBase58CheckError::MismatchedLength {
                 expected: expected_len,
@@ -513,16 +865,158 @@ impl HashType {
         }
 
         // prefix is not present in a binary representation
-        hash.drain(0..self.base58check_prefix().len());
+        hash.drain(0..prefix.len());
         Ok(hash)
     }
 }
 
+macro_rules! any_hash {
+    ($($name:ident),* $(,)?) => {
+        impl HashType {
+            /// Every hash type.
+            ///
+            /// Hash types sharing their prefix and size with an earlier one, such
+            /// as [HashType::SeedEd25519] and [HashType::BlsProofOfPossession],
+            /// are never detected.
+            pub const ALL: &'static [HashType] = &[$(HashType::$name),*];
+        }
+
+        /// Hash of any [HashType], detected from its base58check encoding.
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum AnyHash {
+            $($name($name),)*
+        }
+
+        impl AnyHash {
+            /// Conversion from base58-encoding string (with prefix), of any
+            /// hash type. See [HashType::detect].
+            pub fn from_b58check(data: &str) -> Result<Self, FromBase58CheckError> {
+                let (hash_type, hash) = HashType::detect_hash(data)?;
+                let any_hash = match hash_type {
+                    $(HashType::$name => $name::try_from(hash).map(Self::$name),)*
+                };
+                any_hash.map_err(|_| FromBase58CheckError::InvalidBase58)
+            }
+
+            /// Conversion to base58-encoding string (with prefix).
+            pub fn to_b58check(&self) -> String {
+                self.to_b58check_for(Network::DEFAULT)
+            }
+
+            /// Conversion to base58-encoding string, with the prefix of the
+            /// given network.
+            pub fn to_b58check_for(&self, network: Network) -> String {
+                match self {
+                    $(Self::$name(hash) => hash.to_b58check_for(network),)*
+                }
+            }
+
+            /// Hash type of the hash.
+            pub fn hash_type(&self) -> HashType {
+                match self {
+                    $(Self::$name(_) => HashType::$name,)*
+                }
+            }
+        }
+
+        impl AsRef<[u8]> for AnyHash {
+            fn as_ref(&self) -> &[u8] {
+                match self {
+                    $(Self::$name(hash) => hash.as_ref(),)*
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for AnyHash {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    $(Self::$name(hash) => write!(f, "{}", hash),)*
+                }
+            }
+        }
+
+        $(
+            impl From<$name> for AnyHash {
+                fn from(hash: $name) -> Self {
+                    Self::$name(hash)
+                }
+            }
+        )*
+    };
+}
+
+// Hash types sharing a prefix and a size are detected as the first one.
+any_hash!(
+    ChainId,
+    BlockHash,
+    BlockMetadataHash,
+    BlockPayloadHash,
+    ProtocolHash,
+    ContextHash,
+    OperationHash,
+    OperationListListHash,
+    OperationMetadataHash,
+    OperationMetadataListListHash,
+    CryptoboxPublicKeyHash,
+    ContractKt1Hash,
+    ContractTz1Hash,
+    ContractTz2Hash,
+    ContractTz3Hash,
+    ContractTz4Hash,
+    PublicKeyEd25519,
+    PublicKeySecp256k1,
+    PublicKeyP256,
+    PublicKeyBls,
+    SecretKeyEd25519,
+    SeedEd25519,
+    SecretKeySecp256k1,
+    SecretKeyP256,
+    SecretKeyBls,
+    SecretKeyBlsShare,
+    EncryptedSecretKeyEd25519,
+    EncryptedSecretKeySecp256k1,
+    EncryptedSecretKeyP256,
+    EncryptedSecretKeyBls,
+    UnknownSignature,
+    Ed25519Signature,
+    Secp256k1Signature,
+    P256Signature,
+    BlsSignature,
+    BlsProofOfPossession,
+    BlsPartialSignature,
+    NonceHash,
+    OperationListHash,
+    SmartRollupHash,
+    SmartRollupCommitmentHash,
+    SmartRollupStateHash,
+    SmartRollupInboxHash,
+    SmartRollupMerkelizedPayloadHashesHash,
+    ScriptExprHash,
+    BlindedPublicKeyHash,
+    DalCommitment,
+);
+
+impl std::str::FromStr for AnyHash {
+    type Err = FromBase58CheckError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_b58check(s)
+    }
+}
+
+impl TryFrom<&str> for AnyHash {
+    type Error = FromBase58CheckError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_b58check(value)
+    }
+}
+
 /// Implementation of chain_id.ml -> of_block_hash
 #[inline]
 pub fn chain_id_from_block_hash(block_hash: &BlockHash) -> ChainId {
     let result = crate::blake2b::digest_256(&block_hash.0);
-    ChainId::from_bytes(&result[0..HashType::ChainId.size()])
+    ChainId::try_from_bytes(&result[0..HashType::ChainId.size()])
         .unwrap_or_else(|_| unreachable!("ChainId is created from slice of correct size"))
 }
 
@@ -532,10 +1026,7 @@ macro_rules! pk_with_hash {
             type Hash = $pkh;
 
             fn pk_hash(&self) -> Self::Hash {
-                let hash = blake2b::digest_160(&self.0);
-                // hash size is 20 bytes (160 bits), exactly how many
-                // ContractTz*Hash expect, safe to unwrap
-                Self::Hash::from_bytes(&hash).unwrap()
+                $pkh(blake2b::digest_160(&self.0))
             }
         }
 
@@ -570,61 +1061,339 @@ impl SeedEd25519 {
     pub fn keypair(self) -> Result<(PublicKeyEd25519, SecretKeyEd25519), CryptoError> {
         use ed25519_dalek::{SecretKey, SigningKey};
 
-        let mut v = self.0;
+        // The seed is zeroized when dropped.
         let secret_key: SecretKey =
-            v.as_slice()
+            self.0
+                .as_slice()
                 .try_into()
                 .map_err(|_| CryptoError::InvalidKeySize {
                     expected: ed25519_dalek::SECRET_KEY_LENGTH,
-                    actual: v.len(),
+                    actual: self.0.len(),
                 })?;
-        v.zeroize();
         let sk = SigningKey::from_bytes(&secret_key);
         let pk = sk.verifying_key();
         Ok((
-            PublicKeyEd25519(pk.to_bytes().to_vec()),
+            PublicKeyEd25519(pk.to_bytes()),
             SecretKeyEd25519(sk.to_bytes().to_vec()),
         ))
     }
 }
 
-#[derive(Debug, Error)]
-pub enum PublicKeyError {
-    #[error("Error constructing hash: {0}")]
-    HashError(#[from] FromBytesError),
-    #[error("Blake2b digest error: {0}")]
-    Blake2bError(#[from] Blake2bError),
-}
+#[derive(Debug, Error)]
+pub enum PublicKeyError {
+    #[error("Error constructing hash: {0}")]
+    HashError(#[from] FromBytesError),
+    #[error("Blake2b digest error: {0}")]
+    Blake2bError(#[from] Blake2bError),
+}
+
+impl PublicKeyEd25519 {
+    /// Generates public key hash for public key ed25519
+    pub fn public_key_hash(&self) -> Result<CryptoboxPublicKeyHash, PublicKeyError> {
+        Ok(CryptoboxPublicKeyHash(crate::blake2b::digest_128(
+            self.0.as_ref(),
+        )))
+    }
+}
+
+impl SecretKeyEd25519 {
+    /// Generate a random secret key.
+    pub fn generate<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + rand::CryptoRng,
+    {
+        let mut seed = [0; ed25519_dalek::SECRET_KEY_LENGTH];
+        rng.fill_bytes(&mut seed);
+        let sk = SecretKeyEd25519(seed.to_vec());
+        seed.zeroize();
+        sk
+    }
+
+    /// Derive the public key for the current secret key.
+    pub fn derive_pk(&self) -> Result<PublicKeyEd25519, CryptoError> {
+        let sk = self
+            .0
+            .as_slice()
+            .try_into()
+            .map(ed25519_dalek::SigningKey::from_bytes)
+            .map_err(|_| CryptoError::InvalidKeySize {
+                expected: ed25519_dalek::SECRET_KEY_LENGTH,
+                actual: self.0.len(),
+            })?;
+
+        Ok(PublicKeyEd25519(sk.verifying_key().to_bytes()))
+    }
+
+    pub fn sign<I>(&self, data: I) -> Result<Ed25519Signature, CryptoError>
+    where
+        I: AsRef<[u8]>,
+    {
+        use ed25519_dalek::Signer;
+        use ed25519_dalek::SigningKey;
+
+        let sk = self
+            .0
+            .as_slice()
+            .try_into()
+            .map(SigningKey::from_bytes)
+            .map_err(|_| CryptoError::InvalidKeySize {
+                expected: ed25519_dalek::SECRET_KEY_LENGTH,
+                actual: self.0.len(),
+            })?;
+
+        let payload = crate::blake2b::digest_256(data.as_ref());
+        let signature = sk.sign(&payload);
+        Ok(Ed25519Signature(signature.to_bytes()))
+    }
+
+    /// Sign `data` prefixed by the given watermark.
+    pub fn sign_with_watermark<I>(
+        &self,
+        watermark: &Watermark,
+        data: I,
+    ) -> Result<Ed25519Signature, CryptoError>
+    where
+        I: AsRef<[u8]>,
+    {
+        self.sign(watermark.apply(data.as_ref()))
+    }
+}
+
+impl SecretKeySecp256k1 {
+    fn to_libsecp256k1(&self) -> Result<libsecp256k1::SecretKey, CryptoError> {
+        libsecp256k1::SecretKey::parse_slice(&self.0).map_err(|e| CryptoError::InvalidKey {
+            reason: format!("{:?}", e),
+        })
+    }
+
+    /// Generate a random secret key.
+    pub fn generate<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + rand::CryptoRng,
+    {
+        let mut bytes = [0; CRYPTO_KEY_SIZE];
+        loop {
+            rng.fill_bytes(&mut bytes);
+            if libsecp256k1::SecretKey::parse(&bytes).is_ok() {
+                let sk = SecretKeySecp256k1(bytes.to_vec());
+                bytes.zeroize();
+                return sk;
+            }
+        }
+    }
+
+    /// Derive the public key for the current secret key.
+    pub fn derive_pk(&self) -> Result<PublicKeySecp256k1, CryptoError> {
+        let sk = self.to_libsecp256k1()?;
+        let pk = libsecp256k1::PublicKey::from_secret_key(&sk);
+
+        Ok(PublicKeySecp256k1(pk.serialize_compressed()))
+    }
+
+    /// Sign the blake2b-256 digest of `data`.
+    ///
+    /// The nonce is derived deterministically (RFC 6979), and the resulting
+    /// signature is normalized to low-S, as done by octez.
+    pub fn sign<I>(&self, data: I) -> Result<Secp256k1Signature, CryptoError>
+    where
+        I: AsRef<[u8]>,
+    {
+        let sk = self.to_libsecp256k1()?;
+
+        let payload = crate::blake2b::digest_256(data.as_ref());
+        let msg = libsecp256k1::Message::parse_slice(&payload)
+            .map_err(|_| CryptoError::InvalidMessage)?;
+
+        let (signature, _) = libsecp256k1::sign(&msg, &sk);
+        Ok(Secp256k1Signature(signature.serialize()))
+    }
+
+    /// Sign the blake2b-256 digest of `data`, also returning the recovery id
+    /// of the signature, see [PublicKeySecp256k1::recover].
+    pub fn sign_recoverable<I>(&self, data: I) -> Result<(Secp256k1Signature, u8), CryptoError>
+    where
+        I: AsRef<[u8]>,
+    {
+        let sk = self.to_libsecp256k1()?;
+
+        let payload = crate::blake2b::digest_256(data.as_ref());
+        let msg = libsecp256k1::Message::parse_slice(&payload)
+            .map_err(|_| CryptoError::InvalidMessage)?;
+
+        let (signature, recovery_id) = libsecp256k1::sign(&msg, &sk);
+        Ok((
+            Secp256k1Signature(signature.serialize()),
+            recovery_id.serialize(),
+        ))
+    }
+
+    /// Sign `data` prefixed by the given watermark.
+    pub fn sign_with_watermark<I>(
+        &self,
+        watermark: &Watermark,
+        data: I,
+    ) -> Result<Secp256k1Signature, CryptoError>
+    where
+        I: AsRef<[u8]>,
+    {
+        self.sign(watermark.apply(data.as_ref()))
+    }
+}
+
+impl Secp256k1Signature {
+    fn to_libsecp256k1(self) -> Result<libsecp256k1::Signature, CryptoError> {
+        libsecp256k1::Signature::parse_standard_slice(&self.0)
+            .map_err(|_| CryptoError::InvalidSignature)
+    }
+
+    /// Whether the `s` component of the signature is in the lower half of
+    /// the curve order, as required by octez.
+    pub fn is_low_s(&self) -> Result<bool, CryptoError> {
+        Ok(!self.to_libsecp256k1()?.s.is_high())
+    }
+
+    /// Equivalent signature with a low `s` component.
+    ///
+    /// Both `(r, s)` and `(r, n - s)` are valid signatures of the same
+    /// message, but only the latter is accepted when `s` is high.
+    pub fn normalize_s(&self) -> Result<Self, CryptoError> {
+        let mut signature = self.to_libsecp256k1()?;
+        signature.normalize_s();
+        Ok(Secp256k1Signature(signature.serialize()))
+    }
+}
+
+impl PublicKeySecp256k1 {
+    /// Recover the public key from a `signature` of `bytes`, given the
+    /// recovery id of the signature (between 0 and 3).
+    ///
+    /// As for verification, high-S signatures are rejected.
+    pub fn recover(
+        signature: &Secp256k1Signature,
+        recovery_id: u8,
+        bytes: &[u8],
+    ) -> Result<Self, CryptoError> {
+        let sig = signature.to_libsecp256k1()?;
+        if sig.s.is_high() {
+            return Err(CryptoError::InvalidSignature);
+        }
+        let recovery_id = libsecp256k1::RecoveryId::parse(recovery_id)
+            .map_err(|_| CryptoError::InvalidSignature)?;
+
+        let payload = crate::blake2b::digest_256(bytes);
+        let msg = libsecp256k1::Message::parse_slice(&payload)
+            .map_err(|_| CryptoError::InvalidMessage)?;
+
+        let pk = libsecp256k1::recover(&msg, &sig, &recovery_id)
+            .map_err(|_| CryptoError::InvalidSignature)?;
+        Ok(PublicKeySecp256k1(pk.serialize_compressed()))
+    }
+
+    /// Recover the public key of `pkh` from a `signature` of `bytes`, when the
+    /// recovery id is not known.
+    ///
+    /// Returns `None` if the signature was not made by `pkh`.
+    pub fn recover_with_hash(
+        signature: &Secp256k1Signature,
+        bytes: &[u8],
+        pkh: &ContractTz2Hash,
+    ) -> Result<Option<Self>, CryptoError> {
+        if !signature.is_low_s()? {
+            return Err(CryptoError::InvalidSignature);
+        }
+
+        for recovery_id in 0..4 {
+            if let Ok(pk) = Self::recover(signature, recovery_id, bytes) {
+                if pk.pk_hash() == *pkh {
+                    return Ok(Some(pk));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl SecretKeyP256 {
+    fn to_p256(&self) -> Result<p256::SecretKey, CryptoError> {
+        p256::SecretKey::from_bytes(&self.0).map_err(|e| CryptoError::InvalidKey {
+            reason: e.to_string(),
+        })
+    }
 
-impl PublicKeyEd25519 {
-    /// Generates public key hash for public key ed25519
-    pub fn public_key_hash(&self) -> Result<CryptoboxPublicKeyHash, PublicKeyError> {
-        CryptoboxPublicKeyHash::try_from(crate::blake2b::digest_128(self.0.as_ref()))
-            .map_err(Into::into)
+    /// Generate a random secret key.
+    pub fn generate<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + rand::CryptoRng,
+    {
+        let mut bytes = [0; CRYPTO_KEY_SIZE];
+        loop {
+            rng.fill_bytes(&mut bytes);
+            if p256::SecretKey::from_bytes(bytes).is_ok() {
+                let sk = SecretKeyP256(bytes.to_vec());
+                bytes.zeroize();
+                return sk;
+            }
+        }
     }
-}
 
-impl SecretKeyEd25519 {
-    pub fn sign<I>(&self, data: I) -> Result<Ed25519Signature, CryptoError>
+    /// Derive the public key for the current secret key.
+    pub fn derive_pk(&self) -> Result<PublicKeyP256, CryptoError> {
+        use p256::elliptic_curve::sec1::ToEncodedPoint;
+
+        let pk = self.to_p256()?.public_key();
+
+        PublicKeyP256::try_from(pk.to_encoded_point(true).as_bytes())
+            .map_err(|_| CryptoError::InvalidPublicKey)
+    }
+
+    /// Sign the blake2b-256 digest of `data`.
+    ///
+    /// The nonce is derived deterministically (RFC 6979) with HMAC-SHA256, in
+    /// the same way as for secp256k1.
+    pub fn sign<I>(&self, data: I) -> Result<P256Signature, CryptoError>
     where
         I: AsRef<[u8]>,
     {
-        use ed25519_dalek::Signer;
-        use ed25519_dalek::SigningKey;
+        self.sign_digest(&crate::blake2b::digest_256(data.as_ref()))
+    }
 
-        let sk = self
-            .0
-            .as_slice()
-            .try_into()
-            .map(SigningKey::from_bytes)
-            .map_err(|_| CryptoError::InvalidKeySize {
-                expected: ed25519_dalek::SECRET_KEY_LENGTH,
-                actual: self.0.len(),
-            })?;
+    // Sign a 32-byte digest, with the nonce of RFC 6979.
+    fn sign_digest(&self, digest: &[u8; 32]) -> Result<P256Signature, CryptoError> {
+        use ecdsa::hazmat::SignPrimitive;
+        use hmac_drbg::HmacDRBG;
+        use p256::{FieldBytes, NonZeroScalar, Scalar};
 
-        let payload = crate::blake2b::digest_256(data.as_ref());
-        let signature = sk.sign(&payload);
-        Ok(Ed25519Signature(signature.to_bytes().to_vec()))
+        let secret_scalar = self.to_p256()?.to_secret_scalar();
+
+        let z = Scalar::from_bytes_reduced(FieldBytes::from_slice(digest));
+
+        let mut drbg = HmacDRBG::<sha2::Sha256>::new(&self.0, &z.to_bytes(), &[]);
+        loop {
+            let mut k = FieldBytes::default();
+            drbg.generate_to_slice(&mut k, None);
+
+            let k: Scalar = match NonZeroScalar::from_repr(k) {
+                Some(k) => *k,
+                None => continue,
+            };
+
+            if let Ok(signature) = secret_scalar.try_sign_prehashed(&k, &z) {
+                return P256Signature::try_from(signature.as_ref())
+                    .map_err(|_| CryptoError::InvalidSignature);
+            }
+        }
+    }
+
+    /// Sign `data` prefixed by the given watermark.
+    pub fn sign_with_watermark<I>(
+        &self,
+        watermark: &Watermark,
+        data: I,
+    ) -> Result<P256Signature, CryptoError>
+    where
+        I: AsRef<[u8]>,
+    {
+        self.sign(watermark.apply(data.as_ref()))
     }
 }
 
@@ -671,8 +1440,11 @@ impl PublicKeySignatureVerifier for PublicKeySecp256k1 {
             Some(libsecp256k1::PublicKeyFormat::Compressed),
         )
         .map_err(|_| CryptoError::InvalidPublicKey)?;
-        let sig = libsecp256k1::Signature::parse_standard_slice(signature.as_ref())
-            .map_err(|_| CryptoError::InvalidSignature)?;
+        let sig = signature.to_libsecp256k1()?;
+        // Octez only accepts low-S signatures, which are not malleable.
+        if sig.s.is_high() {
+            return Ok(false);
+        }
 
         let payload = crate::blake2b::digest_256(bytes);
 
@@ -756,7 +1528,7 @@ impl PublicKeySignatureVerifier for PublicKeyP256 {
     }
 }
 
-#[cfg(feature = "bls")]
+#[cfg(any(feature = "bls", feature = "bls-pure"))]
 impl PublicKeySignatureVerifier for PublicKeyBls {
     type Signature = BlsSignature;
     type Error = CryptoError;
@@ -777,6 +1549,17 @@ impl OperationListHash {
     }
 }
 
+impl OperationListListHash {
+    /// Hash of the operations of a block, from the hashes of the operations
+    /// of each validation pass, as in the `operations_hash` of its header.
+    ///
+    /// [blake2b::merkle_proof] proves that an operation is in a validation
+    /// pass, and that the pass is in the block.
+    pub fn calculate(list: &[OperationListHash]) -> Self {
+        OperationListListHash(blake2b::merkle_tree(list))
+    }
+}
+
 impl BlockPayloadHash {
     pub fn calculate(
         predecessor: &BlockHash,
@@ -789,7 +1572,48 @@ impl BlockPayloadHash {
             round.as_ref(),
             operation_list_hash.0.as_ref(),
         ];
-        blake2b::digest_all(&input, 32).map(BlockPayloadHash)
+        Ok(BlockPayloadHash(blake2b::digest_fixed(input)))
+    }
+}
+
+impl OperationHash {
+    /// Hash of an operation, from its binary encoding, signature included.
+    pub fn of_forged(bytes: impl AsRef<[u8]>) -> Self {
+        OperationHash(blake2b::digest_256(bytes.as_ref()))
+    }
+}
+
+impl BlockHash {
+    /// Hash of a block, from the binary encoding of its header, signature
+    /// included.
+    pub fn of_header(bytes: impl AsRef<[u8]>) -> Self {
+        BlockHash(blake2b::digest_256(bytes.as_ref()))
+    }
+}
+
+// Origination nonce of the protocol: the hash of the operation, and the
+// index of the origination within it.
+fn origination_nonce_digest(operation: &OperationHash, index: u32) -> [u8; 20] {
+    blake2b::digest_160(&[&operation.0[..], &index.to_be_bytes()].concat())
+}
+
+impl ContractKt1Hash {
+    /// Address of the contract originated by `operation`.
+    ///
+    /// `index` counts the originations of the operation, including those of
+    /// internal operations, from 0.
+    pub fn from_origination(operation: &OperationHash, index: u32) -> Self {
+        ContractKt1Hash(origination_nonce_digest(operation, index))
+    }
+}
+
+impl SmartRollupHash {
+    /// Address of the smart rollup originated by `operation`.
+    ///
+    /// Smart rollups share the origination nonce of contracts, so `index`
+    /// counts both kinds of originations.
+    pub fn from_origination(operation: &OperationHash, index: u32) -> Self {
+        SmartRollupHash(origination_nonce_digest(operation, index))
     }
 }
 
@@ -809,7 +1633,7 @@ mod tests {
 
     #[test]
     fn test_chain_id_to_b58_string() -> Result<(), anyhow::Error> {
-        let encoded = &ChainId::from_bytes(&hex::decode("8eceda2f")?)?.to_base58_check();
+        let encoded = &ChainId::try_from_bytes(&hex::decode("8eceda2f")?)?.to_base58_check();
         let expected = "NetXgtSLGNJvNye";
         assert_eq!(expected, encoded);
 
@@ -818,7 +1642,7 @@ mod tests {
 
     #[test]
     fn test_chain_id_to_base58_check() -> Result<(), anyhow::Error> {
-        let encoded = ChainId::from_bytes(&hex::decode("8eceda2f")?)?.to_base58_check();
+        let encoded = ChainId::try_from_bytes(&hex::decode("8eceda2f")?)?.to_base58_check();
         let expected = "NetXgtSLGNJvNye";
         assert_eq!(expected, encoded);
 
@@ -857,7 +1681,7 @@ mod tests {
 
     #[test]
     fn test_encode_block_header_genesis_new() -> Result<(), anyhow::Error> {
-        let encoded = BlockHash::from_bytes(&hex::decode(
+        let encoded = BlockHash::try_from_bytes(&hex::decode(
             "8fcf233671b6a04fcf679d2a381c2544ea6c1ea29ba6157776ed8424affa610d",
         )?)?
         .to_base58_check();
@@ -880,7 +1704,7 @@ mod tests {
 
     #[test]
     fn test_encode_block_header_new() -> Result<(), anyhow::Error> {
-        let encoded = BlockHash::from_bytes(&hex::decode(
+        let encoded = BlockHash::try_from_bytes(&hex::decode(
             "46a6aefde9243ae18b191a8d010b7237d5130b3530ce5d1f60457411b2fa632d",
         )?)?
         .to_base58_check();
@@ -903,7 +1727,7 @@ mod tests {
 
     #[test]
     fn test_encode_context_new() -> Result<(), anyhow::Error> {
-        let encoded = ContextHash::from_bytes(&hex::decode(
+        let encoded = ContextHash::try_from_bytes(&hex::decode(
             "934484026d24be9ad40c98341c20e51092dd62bbf470bb9ff85061fa981ebbd9",
         )?)?
         .to_base58_check();
@@ -926,7 +1750,7 @@ mod tests {
 
     #[test]
     fn test_encode_operations_hash_new() -> Result<(), anyhow::Error> {
-        let encoded = OperationListListHash::from_bytes(&hex::decode(
+        let encoded = OperationListListHash::try_from_bytes(&hex::decode(
             "acecbfac449678f1d68b90c7b7a86c9280fd373d872e072f3fb1b395681e7149",
         )?)?
         .to_base58_check();
@@ -936,6 +1760,7 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(not(feature = "mavryk"))]
     #[test]
     fn test_encode_contract_tz1() -> Result<(), anyhow::Error> {
         let decoded = HashType::ContractTz1Hash
@@ -946,17 +1771,20 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(not(feature = "mavryk"))]
     #[test]
     fn test_encode_contract_tz1_new() -> Result<(), anyhow::Error> {
-        let decoded =
-            ContractTz1Hash::from_bytes(&hex::decode("83846eddd5d3c5ed96e962506253958649c84a74")?)?
-                .to_base58_check();
+        let decoded = ContractTz1Hash::try_from_bytes(&hex::decode(
+            "83846eddd5d3c5ed96e962506253958649c84a74",
+        )?)?
+        .to_base58_check();
         let expected = "tz1XdRrrqrMfsFKA8iuw53xHzug9ipr6MuHq";
         assert_eq!(expected, decoded);
 
         Ok(())
     }
 
+    #[cfg(not(feature = "mavryk"))]
     #[test]
     fn test_encode_contract_tz2() -> Result<(), anyhow::Error> {
         let decoded = HashType::ContractTz2Hash
@@ -967,6 +1795,7 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(not(feature = "mavryk"))]
     #[test]
     fn test_encode_contract_tz3() -> Result<(), anyhow::Error> {
         let decoded = HashType::ContractTz3Hash
@@ -987,6 +1816,100 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_copy_and_format() -> Result<(), anyhow::Error> {
+        let encoded = "BKyQ9EofHrgaZKENioHyP4FZNsTmiSEcVmcghgzCC9cGhE7oCET";
+        let hash = BlockHash::from_b58check(encoded)?;
+        let copy = hash;
+        assert_eq!(hash, copy);
+        assert_eq!(std::mem::size_of::<BlockHash>(), 32);
+
+        assert_eq!(hash.to_string(), encoded);
+        assert_eq!(format!("{:?}", hash), format!("BlockHash(\"{}\")", encoded));
+
+        let mut written = String::new();
+        hash.write_b58check_for(Network::Mavryk, &mut written)?;
+        assert_eq!(written, encoded);
+
+        let tz1 = ContractTz1Hash::from_b58check("tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx")?;
+        let mut written = String::new();
+        tz1.write_b58check_for(Network::Mavryk, &mut written)?;
+        assert_eq!(written, "mv18Cw7psUrAAPBpXYd9CtCpHg9EgjHP9KTe");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_network_prefixes() -> Result<(), anyhow::Error> {
+        let bytes = hex::decode("193b2b3f6b8f8e1e6b39b4d442fc2b432f6427a8")?;
+        let tz3 = "tz3NdTPb3Ax2rVW2Kq9QEdzfYFkRwhrQRPhX";
+        let mv3 = HashType::ContractTz3Hash.hash_to_b58check_for(Network::Mavryk, &bytes)?;
+        assert!(mv3.starts_with("mv3"));
+
+        let hash = ContractTz3Hash::from_b58check(&mv3)?;
+        assert_eq!(hash, ContractTz3Hash::from_b58check(tz3)?);
+        assert_eq!(hash.to_b58check_for(Network::Mavryk), mv3);
+        assert_eq!(hash.to_b58check_for(Network::Tezos), tz3);
+        assert!(ContractTz3Hash::from_b58check_for(Network::Mavryk, &mv3).is_ok());
+        assert!(matches!(
+            ContractTz3Hash::from_b58check_for(Network::Tezos, &mv3),
+            Err(FromBase58CheckError::IncorrectBase58Prefix)
+        ));
+
+        // Originated contracts are KT1 on both networks.
+        let kt1 = "KT1EfTusMLoeCAAGd9MZJn5yKzFr6kJU5U91";
+        for network in Network::ALL {
+            let hash = ContractKt1Hash::from_b58check_for(network, kt1)?;
+            assert_eq!(hash.to_b58check_for(network), kt1);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "mavryk"))]
+    #[test]
+    fn test_detect() -> Result<(), anyhow::Error> {
+        for hash_type in HashType::ALL {
+            let bytes = vec![7; hash_type.size()];
+            let encoded = hash_type.hash_to_b58check(&bytes)?;
+
+            let detected = match hash_type {
+                HashType::SeedEd25519 => HashType::SecretKeyEd25519,
+                // Same `BLsig` prefix, see the doc of BlsProofOfPossession.
+                HashType::BlsProofOfPossession => HashType::BlsSignature,
+                hash_type => *hash_type,
+            };
+            assert_eq!(HashType::detect(&encoded)?, detected, "{}", encoded);
+
+            let any_hash = AnyHash::from_b58check(&encoded)?;
+            assert_eq!(any_hash.hash_type(), detected);
+            assert_eq!(any_hash.as_ref(), bytes.as_slice());
+            assert_eq!(any_hash.to_b58check(), encoded);
+        }
+
+        let mv1: AnyHash = "mv18Cw7psUrAAPBpXYd9CtCpHg9EgjHP9KTe".parse()?;
+        assert_eq!(mv1.hash_type(), HashType::ContractTz1Hash);
+        assert_eq!(mv1.to_string(), "tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx");
+
+        let unknown = [0xff; 24].to_base58check();
+        assert!(matches!(
+            HashType::detect(&unknown),
+            Err(FromBase58CheckError::UnknownBase58Prefix)
+        ));
+
+        let mut truncated = HashType::ContractTz1Hash.base58check_prefix().to_vec();
+        truncated.extend([0; 19]);
+        assert!(matches!(
+            AnyHash::from_b58check(&truncated.to_base58check()),
+            Err(FromBase58CheckError::MismatchedLength {
+                expected: 23,
+                actual: 22
+            })
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn test_decode_block_header_hash() -> Result<(), anyhow::Error> {
         let decoded = HashType::BlockHash
@@ -1127,6 +2050,30 @@ mod tests {
             let result = pk.verify_signature(&sig, &message).unwrap();
             assert!(result);
         }
+
+        #[test]
+        fn test_secp256k1_signature_verification_roundtrip(seed in any::<[u8; 32]>(), message in any::<Vec<u8>>()) {
+            prop_assume!(libsecp256k1::SecretKey::parse(&seed).is_ok());
+            let sk = super::SecretKeySecp256k1(seed.to_vec());
+            let pk = sk.derive_pk().unwrap();
+
+            let sig = sk.sign(&message).unwrap();
+
+            let result = pk.verify_signature(&sig, &message).unwrap();
+            assert!(result);
+        }
+
+        #[test]
+        fn test_p256_signature_verification_roundtrip(seed in any::<[u8; 32]>(), message in any::<Vec<u8>>()) {
+            prop_assume!(p256::SecretKey::from_bytes(seed).is_ok());
+            let sk = super::SecretKeyP256(seed.to_vec());
+            let pk = sk.derive_pk().unwrap();
+
+            let sig = sk.sign(&message).unwrap();
+
+            let result = pk.verify_signature(&sig, &message).unwrap();
+            assert!(result);
+        }
     }
 
     #[test]
@@ -1157,6 +2104,167 @@ mod tests {
         assert!(result);
     }
 
+    #[test]
+    fn test_secp256k1_sign() {
+        let sk = SecretKeySecp256k1::from_b58check(
+            "spsk1sheno8Jt8FoBEoamFoNBxUEpjEggNNpepTFc8cEoJBA9QjDJq",
+        )
+        .unwrap();
+        let pk = sk.derive_pk().unwrap();
+        assert_eq!(
+            "sppk7a2WEfU54QzcQZ2EMjihtcxLeRtNTVxHw4FW2e8W5kEJ8ZargSb",
+            pk.to_b58check()
+        );
+
+        let msg = b"hello, test";
+        let sig = sk.sign(msg).unwrap();
+        assert_eq!(
+            "spsig1QLf7cczTbt4UHFGQKUrB2pS3ZTu9wdXR29zKxVPQkhBaiLez6hRcM142ms7HagQa3vuPstvMtYq44y4x4RPcrLu76ZuQ7",
+            sig.to_b58check()
+        );
+        assert!(pk.verify_signature(&sig, msg).unwrap());
+    }
+
+    #[test]
+    fn test_secp256k1_high_s_rejected() {
+        let sk = SecretKeySecp256k1::from_b58check(
+            "spsk1sheno8Jt8FoBEoamFoNBxUEpjEggNNpepTFc8cEoJBA9QjDJq",
+        )
+        .unwrap();
+        let pk = sk.derive_pk().unwrap();
+        let msg = b"hello, test";
+
+        let sig = sk.sign(msg).unwrap();
+        assert!(sig.is_low_s().unwrap());
+
+        // (r, n - s) is a valid, but malleated, signature of the same message.
+        let mut high = libsecp256k1::Signature::parse_standard_slice(sig.as_ref()).unwrap();
+        high.s = -high.s;
+        let high = Secp256k1Signature(high.serialize());
+
+        assert!(!high.is_low_s().unwrap());
+        assert!(!pk.verify_signature(&high, msg).unwrap());
+
+        let normalized = high.normalize_s().unwrap();
+        assert_eq!(sig, normalized);
+        assert!(pk.verify_signature(&normalized, msg).unwrap());
+        assert_eq!(sig, sig.normalize_s().unwrap());
+    }
+
+    #[test]
+    fn test_secp256k1_recover() {
+        let sk = SecretKeySecp256k1::from_b58check(
+            "spsk1sheno8Jt8FoBEoamFoNBxUEpjEggNNpepTFc8cEoJBA9QjDJq",
+        )
+        .unwrap();
+        let pk = sk.derive_pk().unwrap();
+        let pkh = pk.pk_hash();
+        let msg = b"hello, test";
+
+        let (sig, recovery_id) = sk.sign_recoverable(msg).unwrap();
+        assert_eq!(sk.sign(msg).unwrap(), sig);
+
+        assert_eq!(
+            pk,
+            PublicKeySecp256k1::recover(&sig, recovery_id, msg).unwrap()
+        );
+        assert!(!matches!(
+            PublicKeySecp256k1::recover(&sig, recovery_id ^ 1, msg),
+            Ok(other) if other == pk
+        ));
+        assert!(PublicKeySecp256k1::recover(&sig, 4, msg).is_err());
+
+        assert_eq!(
+            Some(pk),
+            PublicKeySecp256k1::recover_with_hash(&sig, msg, &pkh).unwrap()
+        );
+        assert_eq!(
+            None,
+            PublicKeySecp256k1::recover_with_hash(&sig, b"other message", &pkh).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_p256_sign() {
+        let sk =
+            SecretKeyP256::from_b58check("p2sk2bixvFTFTuw9HtD4ucuDsktZTcwRJ5V3gDsQauwE2VTuh6hBiP")
+                .unwrap();
+        let pk = sk.derive_pk().unwrap();
+        assert_eq!(
+            "p2pk65p7HKSGvkMdeK5yckM2nmi59oGNw4ksqdcvwxxF3AV3hopkfGS",
+            pk.to_b58check()
+        );
+
+        let msg = b"hello, message";
+        let sig = sk.sign(msg).unwrap();
+        // Nonces are deterministic, as checked by test_p256_sign_rfc6979.
+        assert_eq!(
+            "p2sigSXGPvUdsZUiLQCdzsHGWcY3T4ncqbTzhLJiPsmfS8opsAUz1uANuHPwobYHBDE4QzbbkNP816QSMECC94PtEEsoRM7Bt6",
+            sig.to_b58check()
+        );
+        assert_eq!(sig, sk.sign(msg).unwrap());
+        assert!(pk.verify_signature(&sig, msg).unwrap());
+        assert!(!pk.verify_signature(&sig, b"another message").unwrap());
+    }
+
+    // RFC 6979, A.2.5: P-256 with SHA-256, which gives the digest to sign in
+    // place of blake2b.
+    #[test]
+    fn test_p256_sign_rfc6979() {
+        use sha2::{Digest, Sha256};
+
+        let sk = SecretKeyP256(
+            hex::decode("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721")
+                .unwrap(),
+        );
+
+        let test_cases = [
+            (
+                "sample",
+                "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716\
+                 f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8",
+            ),
+            (
+                "test",
+                "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367\
+                 019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083",
+            ),
+        ];
+
+        for (msg, expected) in test_cases {
+            let mut digest = [0; 32];
+            digest.copy_from_slice(&Sha256::digest(msg.as_bytes()));
+            let sig = sk.sign_digest(&digest).unwrap();
+            assert_eq!(expected, hex::encode(sig.as_ref()), "{}", msg);
+        }
+    }
+
+    #[test]
+    fn secret_keys_are_redacted() {
+        let sk = SecretKeyEd25519::from_b58check(
+            "edsk3gUfUPyBSfrS9CCgmCiQsTCHGkviBDusMxDJstFtojtc1zcpsh",
+        )
+        .unwrap();
+
+        assert_eq!("<redacted>", sk.to_string());
+        assert_eq!("SecretKeyEd25519(<redacted>)", format!("{:?}", sk));
+        assert_eq!(
+            "Ed25519(SecretKeyEd25519(<redacted>))",
+            format!("{:?}", crate::secret_key::SecretKey::Ed25519(sk.clone()))
+        );
+        assert_eq!(
+            "edsk3gUfUPyBSfrS9CCgmCiQsTCHGkviBDusMxDJstFtojtc1zcpsh",
+            sk.to_b58check()
+        );
+
+        let other = SecretKeyEd25519::from_b58check(
+            "edsk31vznjHSSpGExDMHYASz45VZqXN4DPxvsa4hAyY8dHM28cZzp6",
+        )
+        .unwrap();
+        assert_eq!(sk, sk.clone());
+        assert_ne!(sk, other);
+    }
+
     mod hash_as_json_is_base58check {
         use super::super::*;
 
@@ -1177,6 +2285,26 @@ mod tests {
             };
         }
 
+        // Secret keys are only serialized through `ExposedSecret`.
+        macro_rules! test_secret {
+            ($name:ident, $ty:ident, $h:expr) => {
+                #[test]
+                fn $name() {
+                    for str in $h {
+                        let h = $ty::from_base58_check(str).expect("Invalid secret key");
+                        assert_eq!(str, h.to_base58_check());
+
+                        let json = serde_json::to_string(&ExposedSecret(h.clone()))
+                            .expect("Cannot convert to json");
+                        assert_eq!(json, format!(r#""{}""#, str));
+                        let h1: ExposedSecret<$ty> =
+                            serde_json::from_str(&json).expect("Cannot convert from json");
+                        assert_eq!(h, h1.0);
+                    }
+                }
+            };
+        }
+
         test!(chain_id, ChainId, ["NetXZSsxBpMQeAT"]);
 
         test!(
@@ -1237,19 +2365,27 @@ mod tests {
 
         test!(tz3_hash, ContractTz3Hash, []);
 
+        #[cfg(not(feature = "mavryk"))]
         test!(
             tz4_hash,
             ContractTz4Hash,
             ["tz4FENGt5zkiGaHPm1ya4MgLomgkL1k7Dy7q"]
         );
 
+        #[cfg(feature = "mavryk")]
         test!(
+            mv4_hash,
+            ContractTz4Hash,
+            ["mv4SwSZahemFJit43RZqdVrVEaBE9LzJaKW2"]
+        );
+
+        test_secret!(
             seed_ed25519,
             SeedEd25519,
             ["edsk31vznjHSSpGExDMHYASz45VZqXN4DPxvsa4hAyY8dHM28cZzp6"]
         );
 
-        test!(
+        test_secret!(
             sk_ed25519,
             SecretKeyEd25519,
             [
@@ -1272,7 +2408,19 @@ mod tests {
             ["BLpk1xKLj4548aKR3x7NRwjz5zUnW54MMJAbwTC2qy7owXHvhFomZsYwgAF7agLEzEgrjj5LDeBh"]
         );
 
-        test!(
+        test_secret!(
+            sk_secp256k1,
+            SecretKeySecp256k1,
+            ["spsk1sheno8Jt8FoBEoamFoNBxUEpjEggNNpepTFc8cEoJBA9QjDJq"]
+        );
+
+        test_secret!(
+            sk_p256,
+            SecretKeyP256,
+            ["p2sk2bixvFTFTuw9HtD4ucuDsktZTcwRJ5V3gDsQauwE2VTuh6hBiP"]
+        );
+
+        test_secret!(
             sk_bls,
             SecretKeyBls,
             ["BLsk1WTwJFkLU2P57itDq1cgEUqJK7Fwygvtj49vT4HeLfNBXRgpDA"]
@@ -1319,6 +2467,51 @@ mod tests {
                 "sr1VHPsgVnB3gzRyRULuVV2zmbKyRBMq9gbV"
             ]
         );
+
+        test!(
+            smart_rollup_commitment_hash,
+            SmartRollupCommitmentHash,
+            ["src12UJzB8mg7yU6nWPzicH7ofJbFjyJEbHvwtZdfRXi8DQHNp1LY8"]
+        );
+
+        test!(
+            smart_rollup_state_hash,
+            SmartRollupStateHash,
+            ["srs11y1ZCJfeWnHzoX3rAjcTXiphwg8NvqQhvishP3PU68jgSREuk6"]
+        );
+
+        test!(
+            smart_rollup_inbox_hash,
+            SmartRollupInboxHash,
+            ["srib122WHGK2qNscZABYS6AdFQ3hbG8v2Hf2fA4vnGs9PfRamE9Z9r4"]
+        );
+
+        test!(
+            smart_rollup_merkelized_payload_hashes_hash,
+            SmartRollupMerkelizedPayloadHashesHash,
+            ["srib22LqaDmzQ9AQbmchdUnbs22w4gcTXQwJGLCA6mW3DKdDZxMHoff"]
+        );
+
+        test!(
+            script_expr_hash,
+            ScriptExprHash,
+            [
+                "exprtZBwZUeYYYfUs9B9Rg2ywHezVHnCCnmF9WsDQVrs582dSK63dC",
+                "expru5X1yxJG6ezR2uHMotwMLNmSzQyh5t1vUnhjx4cS6Pv9qE1Sdo"
+            ]
+        );
+
+        test!(
+            blinded_public_key_hash,
+            BlindedPublicKeyHash,
+            ["btz1bRL4X5BWo2Fj4EsBdUwexXqgTf75uf1qa"]
+        );
+
+        test!(
+            dal_commitment,
+            DalCommitment,
+            ["sh1MojypjbCHUxqjEQutzXG5wdXycoHoAHHqWLrfrpHeaGfCedx6igXqYCU5mQgyg9YhLV2KzJ"]
+        );
     }
 
     #[test]
@@ -1361,4 +2554,64 @@ mod tests {
             "vh3Ed4mvDcNYVtskGLCYKKk1aBxJTpQNc46Hyi4EedpGCmgZ4LiG",
         );
     }
+
+    #[test]
+    fn test_hashes_of_bytes() -> Result<(), anyhow::Error> {
+        // Not taken from a chain: these were computed independently of this
+        // crate, with Python's hashlib, as the blake2b hashes of the bytes and,
+        // for originations, of the operation hash followed by the big-endian
+        // 32-bit index, base58check encoded with the prefixes of octez.
+        let operation = OperationHash::of_forged(hex::decode("a5b1c2d3e4f5")?);
+        assert_eq!(
+            operation.to_base58_check(),
+            "oo3i7BCppFt9djtvKqtdugbZbqMKbH7KJG51oq2oS3nnzNni6Qi"
+        );
+
+        let header = (0..100).collect::<Vec<u8>>();
+        assert_eq!(
+            BlockHash::of_header(header).to_base58_check(),
+            "BLQGG1VvkLqkUxYpVfCyr7Hjkg51i4fHiQNhTHQB4STxssjmDth"
+        );
+
+        assert_eq!(
+            ContractKt1Hash::from_origination(&operation, 0).to_base58_check(),
+            "KT1PiHWVSvUxPSPqjfU9PX7n3rvkoT3qmJo7"
+        );
+        assert_eq!(
+            ContractKt1Hash::from_origination(&operation, 1).to_base58_check(),
+            "KT1M3pDdDs6YrkPQMjQzpJD1wsVhtjoeRPV2"
+        );
+        assert_eq!(
+            SmartRollupHash::from_origination(&operation, 0).to_base58_check(),
+            "sr1MArrk83c4gdU24aJvQztseWbsrRNSBSWc"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_operation_inclusion_proof() {
+        let passes = [0u8, 1, 3, 5]
+            .iter()
+            .map(|&len| {
+                (0..len)
+                    .map(|i| OperationHash::of_forged([len, i]))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let pass_hashes = passes
+            .iter()
+            .map(|ops| OperationListHash::calculate(ops))
+            .collect::<Vec<_>>();
+        let operations_hash = OperationListListHash::calculate(&pass_hashes);
+
+        // The 3rd operation of the last validation pass.
+        let operation = passes[3][2];
+        let in_pass = blake2b::merkle_proof(&passes[3], 2).unwrap();
+        let in_block = blake2b::merkle_proof(&pass_hashes, 3).unwrap();
+        assert!(in_pass.verify(operation, pass_hashes[3]));
+        assert!(in_block.verify(pass_hashes[3], operations_hash));
+        assert!(in_block.verify(in_pass.root(operation), operations_hash));
+        assert!(!in_block.verify(in_pass.root(passes[3][1]), operations_hash));
+    }
 }
@@ -0,0 +1,326 @@
+// SPDX-FileCopyrightText: 2024 TriliTech <contact@trili.tech>
+//
+// SPDX-License-Identifier: MIT
+
+//! Hash types defined outside of this crate.
+
+/// Defines a hash type of `size` bytes, with the given base58check `prefix`.
+///
+/// The hash types of this crate are defined by this macro: the type is
+/// `Copy`, implements [HashTrait](crate::hash::HashTrait), encodes to and from
+/// base58check, and implements serde, `HasEncoding`, `NomReader` and
+/// `BinWriter`. It is for hash kinds that [HashType](crate::hash::HashType)
+/// does not know about, such as those of a kernel.
+///
+/// ```
+/// use tezos_crypto_rs::define_prefixed_hash;
+/// use tezos_crypto_rs::hash::HashTrait;
+///
+/// define_prefixed_hash!(
+///     /// Hash of a kernel message.
+///     pub MessageHash,
+///     prefix: [3, 255, 138, 145, 110],
+///     size: 32,
+/// );
+///
+/// let hash = MessageHash([0; 32]);
+/// let encoded = hash.to_b58check();
+/// assert!(encoded.starts_with("srib1"));
+/// assert_eq!(MessageHash::from_b58check(&encoded).unwrap(), hash);
+/// ```
+#[macro_export]
+macro_rules! define_prefixed_hash {
+    (
+        $(#[$meta:meta])*
+        $vis:vis $name:ident,
+        prefix: $prefix:expr,
+        size: $size:expr $(,)?
+    ) => {
+        $crate::define_prefixed_hash!(
+            @define
+            $(#[$meta])*
+            $vis $name(pub),
+            prefix: |_network| &$prefix,
+            size: $size,
+        );
+    };
+    // The prefix of the hash types of this crate depends on the network.
+    (
+        @define
+        $(#[$meta:meta])*
+        $vis:vis $name:ident($field_vis:vis),
+        prefix: |$network:ident| $prefix:expr,
+        size: $size:expr $(,)?
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        $vis struct $name($field_vis [u8; $size]);
+
+        impl $crate::hash::HashTrait for $name {
+            fn hash_size() -> usize {
+                $size
+            }
+
+            fn base58check_prefix_for($network: $crate::hash::Network) -> &'static [u8] {
+                $prefix
+            }
+
+            fn try_from_bytes(
+                bytes: &[u8],
+            ) -> ::std::result::Result<Self, $crate::hash::FromBytesError> {
+                Self::try_from(bytes)
+            }
+        }
+
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                $crate::hash::HashTrait::write_b58check_for(self, $crate::hash::Network::DEFAULT, f)
+            }
+        }
+
+        impl ::std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_tuple(stringify!($name))
+                    .field(&format_args!("\"{}\"", self))
+                    .finish()
+            }
+        }
+
+        impl ::std::str::FromStr for $name {
+            type Err = $crate::base58::FromBase58CheckError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                $crate::hash::HashTrait::from_b58check(s)
+            }
+        }
+
+        impl ::std::convert::TryFrom<&str> for $name {
+            type Error = $crate::base58::FromBase58CheckError;
+
+            fn try_from(s: &str) -> ::std::result::Result<Self, Self::Error> {
+                $crate::hash::HashTrait::from_b58check(s)
+            }
+        }
+
+        impl ::std::convert::TryFrom<&[u8]> for $name {
+            type Error = $crate::hash::FromBytesError;
+
+            fn try_from(bytes: &[u8]) -> ::std::result::Result<Self, Self::Error> {
+                ::std::convert::TryInto::try_into(bytes)
+                    .map(Self)
+                    .map_err(|_| $crate::hash::FromBytesError::InvalidSize)
+            }
+        }
+
+        impl ::std::convert::TryFrom<::std::vec::Vec<u8>> for $name {
+            type Error = $crate::hash::FromBytesError;
+
+            fn try_from(bytes: ::std::vec::Vec<u8>) -> ::std::result::Result<Self, Self::Error> {
+                Self::try_from(bytes.as_slice())
+            }
+        }
+
+        impl ::std::convert::AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl ::std::convert::From<$name> for ::std::vec::Vec<u8> {
+            fn from(hash: $name) -> Self {
+                hash.0.to_vec()
+            }
+        }
+
+        impl $crate::__private::serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+            {
+                if serializer.is_human_readable() {
+                    serializer.collect_str(self)
+                } else {
+                    serializer.serialize_newtype_struct(stringify!($name), &self.0[..])
+                }
+            }
+        }
+
+        impl<'de> $crate::__private::serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                use $crate::__private::serde::de::{Error, SeqAccess, Visitor};
+                use $crate::__private::serde::{Deserialize, Deserializer};
+
+                struct HashVisitor;
+
+                impl HashVisitor {
+                    fn from_bytes<E: Error>(bytes: &[u8]) -> ::std::result::Result<$name, E> {
+                        $name::try_from(bytes).map_err(|e| {
+                            E::custom(format!("error constructing hash from bytes: {}", e))
+                        })
+                    }
+                }
+
+                impl<'de> Visitor<'de> for HashVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                        f.write_str("either sequence of bytes or base58 encoded data expected")
+                    }
+
+                    fn visit_str<E: Error>(self, v: &str) -> ::std::result::Result<$name, E> {
+                        <$name as $crate::hash::HashTrait>::from_b58check(v).map_err(|e| {
+                            E::custom(format!("error constructing hash from base58check: {}", e))
+                        })
+                    }
+
+                    fn visit_newtype_struct<E>(self, e: E) -> ::std::result::Result<$name, E::Error>
+                    where
+                        E: Deserializer<'de>,
+                    {
+                        let bytes = <::std::vec::Vec<u8>>::deserialize(e)?;
+                        Self::from_bytes(&bytes)
+                    }
+
+                    fn visit_seq<A>(self, mut seq: A) -> ::std::result::Result<$name, A::Error>
+                    where
+                        A: SeqAccess<'de>,
+                    {
+                        let bytes = seq
+                            .next_element::<::std::vec::Vec<u8>>()?
+                            .ok_or_else(|| A::Error::custom("no hash bytes"))?;
+                        Self::from_bytes(&bytes)
+                    }
+                }
+
+                if deserializer.is_human_readable() {
+                    deserializer.deserialize_str(HashVisitor)
+                } else {
+                    deserializer.deserialize_newtype_struct(stringify!($name), HashVisitor)
+                }
+            }
+        }
+
+        impl $crate::__private::tezos_data_encoding::encoding::HasEncoding for $name {
+            fn encoding() -> $crate::__private::tezos_data_encoding::encoding::Encoding {
+                $crate::__private::tezos_data_encoding::encoding::Encoding::Hash(stringify!($name))
+            }
+        }
+
+        impl<'a> $crate::__private::tezos_data_encoding::nom::NomReader<'a> for $name {
+            fn nom_read(
+                input: &'a [u8],
+            ) -> $crate::__private::tezos_data_encoding::nom::NomResult<'a, Self> {
+                use $crate::__private::nom::{bytes::complete::take, combinator::map};
+
+                map(take(<Self as $crate::hash::HashTrait>::hash_size()), |bytes: &[u8]| {
+                    let mut hash = [0; $size];
+                    hash.copy_from_slice(bytes);
+                    Self(hash)
+                })(input)
+            }
+        }
+
+        impl $crate::__private::tezos_data_encoding::enc::BinWriter for $name {
+            fn bin_write(
+                &self,
+                out: &mut ::std::vec::Vec<u8>,
+            ) -> $crate::__private::tezos_data_encoding::enc::BinResult {
+                $crate::__private::tezos_data_encoding::enc::put_bytes(&self.0, out);
+                Ok(())
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hash::{HashTrait, Network, SmartRollupInboxHash};
+    use tezos_data_encoding::enc::BinWriter;
+    use tezos_data_encoding::encoding::{Encoding, HasEncoding};
+    use tezos_data_encoding::nom::NomReader;
+
+    define_prefixed_hash!(
+        /// Same encoding as `SmartRollupInboxHash`.
+        pub InboxHash,
+        prefix: [3, 255, 138, 145, 110],
+        size: 32,
+    );
+
+    define_prefixed_hash!(ShortHash, prefix: [1, 2], size: 4);
+
+    #[test]
+    fn base58check() {
+        let expected = SmartRollupInboxHash::try_from_bytes(&[42; 32]).unwrap();
+        let srib1 = expected.to_b58check();
+        let hash = InboxHash::from_b58check(&srib1).unwrap();
+        assert_eq!(hash, InboxHash([42; 32]));
+        assert_eq!(hash.to_b58check(), srib1);
+        assert_eq!(format!("{:?}", hash), format!("InboxHash(\"{}\")", srib1));
+        assert!(InboxHash::from_b58check(&srib1[1..]).is_err());
+
+        let short = ShortHash([1, 2, 3, 4]);
+        assert_eq!(short, short.to_b58check().parse().unwrap());
+        assert!(InboxHash::from_b58check(&short.to_string()).is_err());
+        assert!(ShortHash::try_from(&[0; 5][..]).is_err());
+    }
+
+    #[test]
+    fn hash_trait() {
+        fn same_encodings<H: HashTrait, B: HashTrait>(hash: &H, builtin: &B) {
+            assert_eq!(H::hash_size(), B::hash_size());
+            for network in Network::ALL {
+                assert_eq!(
+                    hash.to_b58check_for(network),
+                    builtin.to_b58check_for(network)
+                );
+            }
+        }
+
+        let hash = InboxHash::try_from_bytes(&[42; 32]).unwrap();
+        let builtin = SmartRollupInboxHash::try_from_bytes(&[42; 32]).unwrap();
+        same_encodings(&hash, &builtin);
+        assert_eq!(
+            InboxHash::from_b58check_for(Network::Mavryk, &builtin.to_b58check()).unwrap(),
+            hash
+        );
+        assert!(InboxHash::try_from_bytes(&[42; 31]).is_err());
+    }
+
+    #[test]
+    fn encodings() {
+        let hash = InboxHash([7; 32]);
+
+        let mut bytes = Vec::new();
+        hash.bin_write(&mut bytes).unwrap();
+        assert_eq!(bytes, [7; 32]);
+        let (rest, decoded) = InboxHash::nom_read(&bytes).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(decoded, hash);
+        assert!(InboxHash::nom_read(&bytes[1..]).is_err());
+        assert!(matches!(InboxHash::encoding(), Encoding::Hash("InboxHash")));
+
+        let json = serde_json::to_string(&hash).unwrap();
+        assert_eq!(json, format!("\"{}\"", hash));
+        assert_eq!(serde_json::from_str::<InboxHash>(&json).unwrap(), hash);
+
+        // Binary formats, which are not human-readable, go through
+        // `visit_newtype_struct`, with the same bytes as built-in hashes.
+        let binary = bincode::serialize(&hash).unwrap();
+        let builtin = SmartRollupInboxHash::try_from_bytes(&[7; 32]).unwrap();
+        assert_eq!(binary, bincode::serialize(&builtin).unwrap());
+        assert_eq!(bincode::deserialize::<InboxHash>(&binary).unwrap(), hash);
+        assert!(bincode::deserialize::<InboxHash>(&binary[..binary.len() - 1]).is_err());
+        let short = bincode::serialize(&[7u8; 31].to_vec()).unwrap();
+        assert!(bincode::deserialize::<InboxHash>(&short).is_err());
+
+        // Formats that only know sequences go through `visit_seq`.
+        use serde::de::{value, Deserialize, IntoDeserializer};
+        let seq: value::SeqDeserializer<_, value::Error> = vec![vec![7u8; 32]].into_deserializer();
+        assert_eq!(InboxHash::deserialize(seq).unwrap(), hash);
+        let seq: value::SeqDeserializer<_, value::Error> = vec![vec![7u8; 31]].into_deserializer();
+        assert!(InboxHash::deserialize(seq).is_err());
+    }
+}
@@ -0,0 +1,439 @@
+// SPDX-FileCopyrightText: 2024 Trilitech <contact@trili.tech>
+//
+// SPDX-License-Identifier: MIT
+
+//! High watermarks, protecting bakers against double signing.
+//!
+//! For every key and chain, the level and round of the last signed block,
+//! preattestation and attestation are recorded. Signing consensus data at a
+//! lower (level, round) is refused, as is signing different data at the same
+//! (level, round). Signing the exact same bytes again is allowed, as done by
+//! the octez signer.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use tezos_data_encoding::enc::BinWriter;
+use tezos_data_encoding::encoding::HasEncoding;
+use tezos_data_encoding::nom::NomReader;
+use tezos_data_encoding::types::SizedBytes;
+use thiserror::Error;
+
+use crate::blake2b;
+use crate::hash::{ChainId, HashTrait};
+use crate::public_key_hash::PublicKeyHash;
+use crate::watermark::Watermark;
+use crate::{CryptoError, FileError};
+
+// Offsets in watermarked bytes: tag (1 byte) followed by chain id (4 bytes).
+const CHAIN_ID_OFFSET: usize = 1;
+const PAYLOAD_OFFSET: usize = 5;
+// Block header: level, proto, predecessor, timestamp, validation pass,
+// operations hash, then fitness.
+const BLOCK_LEVEL_OFFSET: usize = PAYLOAD_OFFSET;
+const BLOCK_FITNESS_OFFSET: usize = PAYLOAD_OFFSET + 4 + 1 + 32 + 8 + 1 + 32;
+// Consensus operation: branch, tag, slot, then level and round.
+const CONSENSUS_LEVEL_OFFSET: usize = PAYLOAD_OFFSET + 32 + 1 + 2;
+const CONSENSUS_ROUND_OFFSET: usize = CONSENSUS_LEVEL_OFFSET + 4;
+
+/// Possible errors of high watermark protection.
+#[derive(Debug, Error)]
+pub enum HighWatermarkError {
+    /// The watermarked bytes are too short to contain a level and round.
+    #[error("invalid {0:?} payload")]
+    InvalidPayload(ConsensusKind),
+    /// The data was already signed at this level and round, or later.
+    #[error(
+        "{kind:?} at level {level}, round {round} is below high watermark (level {hwm_level}, round {hwm_round})"
+    )]
+    BelowHighWatermark {
+        kind: ConsensusKind,
+        level: i32,
+        round: i32,
+        hwm_level: i32,
+        hwm_round: i32,
+    },
+    /// The high watermark file could not be read or written.
+    #[error("high watermark {0}")]
+    File(#[from] FileError),
+    /// Signing failed.
+    #[error("signing error: {0}")]
+    Crypto(#[from] CryptoError),
+}
+
+/// Kind of consensus data protected by high watermarks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, HasEncoding, BinWriter, NomReader)]
+pub enum ConsensusKind {
+    Block,
+    PreAttestation,
+    Attestation,
+}
+
+/// Level and round of watermarked consensus data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsensusPayload {
+    pub kind: ConsensusKind,
+    pub chain_id: ChainId,
+    pub level: i32,
+    pub round: i32,
+}
+
+impl ConsensusPayload {
+    /// Decode the level and round of watermarked bytes, as received by a signer.
+    ///
+    /// Returns `None` for data that is not a block, preattestation or
+    /// attestation, which is not protected by high watermarks.
+    pub fn decode(bytes: &[u8]) -> Result<Option<Self>, HighWatermarkError> {
+        let kind = match bytes.first() {
+            Some(&Watermark::BLOCK_TAG) => ConsensusKind::Block,
+            Some(&Watermark::PRE_ATTESTATION_TAG) => ConsensusKind::PreAttestation,
+            Some(&Watermark::ATTESTATION_TAG) => ConsensusKind::Attestation,
+            _ => return Ok(None),
+        };
+        let invalid = || HighWatermarkError::InvalidPayload(kind);
+
+        let chain_id = bytes
+            .get(CHAIN_ID_OFFSET..PAYLOAD_OFFSET)
+            .and_then(|bytes| ChainId::try_from_bytes(bytes).ok())
+            .ok_or_else(invalid)?;
+
+        let (level, round) = match kind {
+            ConsensusKind::Block => {
+                let level = read_i32(bytes, BLOCK_LEVEL_OFFSET).ok_or_else(invalid)?;
+                let fitness_length = read_i32(bytes, BLOCK_FITNESS_OFFSET)
+                    .and_then(|length| usize::try_from(length).ok())
+                    .filter(|length| *length >= 4)
+                    .ok_or_else(invalid)?;
+                // The round is the last element of the fitness.
+                let round =
+                    read_i32(bytes, BLOCK_FITNESS_OFFSET + fitness_length).ok_or_else(invalid)?;
+                (level, round)
+            }
+            ConsensusKind::PreAttestation | ConsensusKind::Attestation => (
+                read_i32(bytes, CONSENSUS_LEVEL_OFFSET).ok_or_else(invalid)?,
+                read_i32(bytes, CONSENSUS_ROUND_OFFSET).ok_or_else(invalid)?,
+            ),
+        };
+
+        Ok(Some(Self {
+            kind,
+            chain_id,
+            level,
+            round,
+        }))
+    }
+}
+
+fn read_i32(bytes: &[u8], offset: usize) -> Option<i32> {
+    let bytes = bytes.get(offset..offset + 4)?;
+    Some(i32::from_be_bytes(bytes.try_into().ok()?))
+}
+
+/// Last signed level and round, for a key, chain and kind of consensus data.
+#[derive(Debug, Clone, PartialEq, Eq, HasEncoding, BinWriter, NomReader)]
+pub struct HighWatermark {
+    pub level: i32,
+    pub round: i32,
+    /// Blake2b digest of the signed bytes.
+    digest: SizedBytes<32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, HasEncoding, BinWriter, NomReader)]
+struct HighWatermarkEntry {
+    kind: ConsensusKind,
+    chain_id: ChainId,
+    pkh: PublicKeyHash,
+    high_watermark: HighWatermark,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, HasEncoding, BinWriter, NomReader)]
+struct HighWatermarkFile {
+    #[encoding(dynamic)]
+    entries: Vec<HighWatermarkEntry>,
+}
+
+type HighWatermarkKey = (ConsensusKind, ChainId, PublicKeyHash);
+
+/// Store of high watermarks, optionally persisted to a file.
+#[derive(Debug, Default)]
+pub struct HighWatermarks {
+    path: Option<PathBuf>,
+    high_watermarks: BTreeMap<HighWatermarkKey, HighWatermark>,
+}
+
+impl HighWatermarks {
+    /// In-memory store, which is lost on exit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store persisted to the given file, loading its content if it exists.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, HighWatermarkError> {
+        let path = path.as_ref().to_path_buf();
+
+        let high_watermarks = match crate::read_encoded::<HighWatermarkFile>(&path)? {
+            Some(file) => file
+                .entries
+                .into_iter()
+                .map(|e| ((e.kind, e.chain_id, e.pkh), e.high_watermark))
+                .collect(),
+            None => BTreeMap::new(),
+        };
+
+        Ok(Self {
+            path: Some(path),
+            high_watermarks,
+        })
+    }
+
+    /// The high watermark of a key, for a chain and kind of consensus data.
+    pub fn get(
+        &self,
+        kind: ConsensusKind,
+        chain_id: &ChainId,
+        pkh: &PublicKeyHash,
+    ) -> Option<&HighWatermark> {
+        self.high_watermarks.get(&(kind, *chain_id, pkh.clone()))
+    }
+
+    /// Check that the watermarked bytes may be signed by the given key.
+    pub fn check(&self, pkh: &PublicKeyHash, bytes: &[u8]) -> Result<(), HighWatermarkError> {
+        let payload = match ConsensusPayload::decode(bytes)? {
+            Some(payload) => payload,
+            None => return Ok(()),
+        };
+
+        match self.get(payload.kind, &payload.chain_id, pkh) {
+            Some(hwm)
+                if (payload.level, payload.round) < (hwm.level, hwm.round)
+                    || ((payload.level, payload.round) == (hwm.level, hwm.round)
+                        && hwm.digest.0 != blake2b::digest_256(bytes)) =>
+            {
+                Err(HighWatermarkError::BelowHighWatermark {
+                    kind: payload.kind,
+                    level: payload.level,
+                    round: payload.round,
+                    hwm_level: hwm.level,
+                    hwm_round: hwm.round,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that the watermarked bytes may be signed by the given key, and
+    /// record them as the new high watermark.
+    ///
+    /// The store is persisted before returning, if it is backed by a file.
+    pub fn check_and_record(
+        &mut self,
+        pkh: &PublicKeyHash,
+        bytes: &[u8],
+    ) -> Result<(), HighWatermarkError> {
+        self.check(pkh, bytes)?;
+
+        let payload = match ConsensusPayload::decode(bytes)? {
+            Some(payload) => payload,
+            None => return Ok(()),
+        };
+
+        let high_watermark = HighWatermark {
+            level: payload.level,
+            round: payload.round,
+            digest: SizedBytes(blake2b::digest_256(bytes)),
+        };
+        self.high_watermarks.insert(
+            (payload.kind, payload.chain_id, pkh.clone()),
+            high_watermark,
+        );
+
+        self.persist()
+    }
+
+    fn persist(&self) -> Result<(), HighWatermarkError> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        let file = HighWatermarkFile {
+            entries: self
+                .high_watermarks
+                .iter()
+                .map(|((kind, chain_id, pkh), hwm)| HighWatermarkEntry {
+                    kind: *kind,
+                    chain_id: *chain_id,
+                    pkh: pkh.clone(),
+                    high_watermark: hwm.clone(),
+                })
+                .collect(),
+        };
+        crate::write_encoded(path, &file)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::secret_key::SecretKey;
+    use std::fs;
+
+    fn chain_id() -> ChainId {
+        ChainId::from_b58check("NetXdQprcVkpaWU").unwrap()
+    }
+
+    fn block(level: i32, round: i32, seed: u8) -> Vec<u8> {
+        let mut bytes = Watermark::Block(chain_id()).to_bytes();
+        bytes.extend_from_slice(&level.to_be_bytes());
+        bytes.push(1); // proto
+        bytes.extend_from_slice(&[seed; 32]); // predecessor
+        bytes.extend_from_slice(&[0; 8]); // timestamp
+        bytes.push(4); // validation passes
+        bytes.extend_from_slice(&[0; 32]); // operations hash
+
+        let fitness = [
+            &[0, 0, 0, 1, 2][..],
+            &[0, 0, 0, 4],
+            &level.to_be_bytes(),
+            &[0, 0, 0, 0],
+            &[0, 0, 0, 4, 0xff, 0xff, 0xff, 0xff],
+            &[0, 0, 0, 4],
+            &round.to_be_bytes(),
+        ]
+        .concat();
+        bytes.extend_from_slice(&(fitness.len() as i32).to_be_bytes());
+        bytes.extend_from_slice(&fitness);
+        bytes.extend_from_slice(&[seed; 32]); // protocol data
+        bytes
+    }
+
+    fn attestation(watermark: Watermark, level: i32, round: i32) -> Vec<u8> {
+        let mut bytes = watermark.to_bytes();
+        bytes.extend_from_slice(&[0; 32]); // branch
+        bytes.push(21); // tag
+        bytes.extend_from_slice(&[0, 3]); // slot
+        bytes.extend_from_slice(&level.to_be_bytes());
+        bytes.extend_from_slice(&round.to_be_bytes());
+        bytes.extend_from_slice(&[0; 32]); // block payload hash
+        bytes
+    }
+
+    fn pkh() -> PublicKeyHash {
+        PublicKeyHash::from_b58check("tz1Ke2h7sDdakHJQh8WX4Z372du1KChsksyU").unwrap()
+    }
+
+    #[test]
+    fn decode() {
+        let payload = ConsensusPayload::decode(&block(1234, 5, 0)).unwrap();
+        assert_eq!(
+            Some(ConsensusPayload {
+                kind: ConsensusKind::Block,
+                chain_id: chain_id(),
+                level: 1234,
+                round: 5
+            }),
+            payload
+        );
+
+        let bytes = attestation(Watermark::PreAttestation(chain_id()), 42, 1);
+        let payload = ConsensusPayload::decode(&bytes).unwrap().unwrap();
+        assert_eq!(
+            (ConsensusKind::PreAttestation, 42, 1),
+            (payload.kind, payload.level, payload.round)
+        );
+
+        let bytes = attestation(Watermark::Attestation(chain_id()), 43, 0);
+        let payload = ConsensusPayload::decode(&bytes).unwrap().unwrap();
+        assert_eq!(
+            (ConsensusKind::Attestation, 43, 0),
+            (payload.kind, payload.level, payload.round)
+        );
+
+        assert_eq!(
+            None,
+            ConsensusPayload::decode(&Watermark::GenericOperation.apply(&[0; 64])).unwrap()
+        );
+        assert!(matches!(
+            ConsensusPayload::decode(&block(1, 0, 0)[..50]),
+            Err(HighWatermarkError::InvalidPayload(ConsensusKind::Block))
+        ));
+    }
+
+    #[test]
+    fn check_and_record() {
+        let mut hwm = HighWatermarks::new();
+        let pkh = pkh();
+
+        hwm.check_and_record(&pkh, &block(10, 1, 0)).unwrap();
+
+        // Same bytes may be signed again, other bytes at the same level and round may not.
+        hwm.check_and_record(&pkh, &block(10, 1, 0)).unwrap();
+        assert!(matches!(
+            hwm.check_and_record(&pkh, &block(10, 1, 1)),
+            Err(HighWatermarkError::BelowHighWatermark { .. })
+        ));
+        assert!(hwm.check(&pkh, &block(10, 0, 0)).is_err());
+        assert!(hwm.check(&pkh, &block(9, 5, 0)).is_err());
+
+        hwm.check_and_record(&pkh, &block(10, 2, 0)).unwrap();
+        hwm.check_and_record(&pkh, &block(11, 0, 0)).unwrap();
+        assert_eq!(
+            (11, 0),
+            hwm.get(ConsensusKind::Block, &chain_id(), &pkh)
+                .map(|hwm| (hwm.level, hwm.round))
+                .unwrap()
+        );
+
+        // Kinds of consensus data, keys and chains are independent.
+        let attestation = attestation(Watermark::Attestation(chain_id()), 10, 0);
+        hwm.check_and_record(&pkh, &attestation).unwrap();
+
+        let other_pkh =
+            PublicKeyHash::from_b58check("tz2VGBaXuS6rnaa5hpC92qkgadRJKdEbeGwc").unwrap();
+        hwm.check_and_record(&other_pkh, &block(1, 0, 0)).unwrap();
+
+        // Data other than consensus data is not protected.
+        let transfer = Watermark::GenericOperation.apply(&[0; 64]);
+        hwm.check_and_record(&pkh, &transfer).unwrap();
+        hwm.check_and_record(&pkh, &transfer).unwrap();
+    }
+
+    #[test]
+    fn persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("high_watermarks");
+        let pkh = pkh();
+
+        let mut hwm = HighWatermarks::open(&path).unwrap();
+        hwm.check_and_record(&pkh, &block(10, 1, 0)).unwrap();
+        drop(hwm);
+
+        let hwm = HighWatermarks::open(&path).unwrap();
+        assert!(hwm.check(&pkh, &block(10, 1, 1)).is_err());
+        assert!(hwm.check(&pkh, &block(10, 2, 0)).is_ok());
+
+        fs::write(&path, [1, 2, 3]).unwrap();
+        assert!(matches!(
+            HighWatermarks::open(&path),
+            Err(HighWatermarkError::File(FileError::Corrupted))
+        ));
+    }
+
+    #[test]
+    fn sign_with_high_watermark() {
+        let sk = SecretKey::from_b58check("edsk3vifWnPCr8jXyhnt1YLa5KeNYTPfHENDq9gxqAA8ERkvEigYMe")
+            .unwrap();
+        let mut hwm = HighWatermarks::new();
+
+        let signature = sk
+            .sign_with_high_watermark(&mut hwm, &block(10, 0, 0))
+            .unwrap();
+        assert_eq!(sk.sign(block(10, 0, 0)).unwrap(), signature);
+
+        assert!(matches!(
+            sk.sign_with_high_watermark(&mut hwm, &block(9, 0, 0)),
+            Err(HighWatermarkError::BelowHighWatermark { .. })
+        ));
+    }
+}
@@ -11,13 +11,31 @@ use thiserror::Error;
 #[macro_use]
 pub mod blake2b;
 pub mod base58;
-#[cfg(feature = "bls")]
+pub mod batch;
+#[cfg(any(feature = "bls", feature = "bls-pure"))]
 pub mod bls;
+pub mod contract;
+pub mod derivation;
+pub mod genesis;
 #[macro_use]
 pub mod hash;
+pub mod high_watermark;
+pub mod nonce;
 pub mod public_key;
 pub mod public_key_hash;
+#[cfg(feature = "remote-signer")]
+pub mod remote_signer;
+pub mod secret_key;
 pub mod signature;
+pub mod watermark;
+
+// Used by exported macros.
+#[doc(hidden)]
+pub mod __private {
+    pub use nom;
+    pub use serde;
+    pub use tezos_data_encoding;
+}
 
 #[derive(Debug, Error)]
 pub enum CryptoError {
@@ -45,6 +63,20 @@ pub enum CryptoError {
     SignatureType(#[from] signature::TryFromSignatureError),
 }
 
+/// Possible errors of the files the crate stores its state in.
+#[derive(Debug, Error)]
+pub enum FileError {
+    /// The file could not be read or written.
+    #[error("file error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The file is not a valid encoding.
+    #[error("corrupted file")]
+    Corrupted,
+    /// The state could not be encoded, to be written to the file.
+    #[error("encoding error: {0}")]
+    Encoding(#[from] tezos_data_encoding::enc::BinError),
+}
+
 /// Public key that support hashing.
 pub trait PublicKeyWithHash {
     type Hash;
@@ -62,4 +94,83 @@ pub trait PublicKeySignatureVerifier {
         signature: &Self::Signature,
         msg: &[u8],
     ) -> Result<bool, Self::Error>;
+
+    /// Verify a signature of `msg` prefixed by the given watermark.
+    fn verify_with_watermark(
+        &self,
+        watermark: &watermark::Watermark,
+        signature: &Self::Signature,
+        msg: &[u8],
+    ) -> Result<bool, Self::Error> {
+        self.verify_signature(signature, &watermark.apply(msg))
+    }
+}
+
+// Write to a temporary file, then rename it over the previous one, so that
+// the file is never left partially written. The file is only readable by its
+// owner, and the directory is synced so that the rename survives a crash.
+pub(crate) fn write_atomically(path: &std::path::Path, bytes: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut tmp_path = path.to_path_buf().into_os_string();
+    tmp_path.push(".tmp");
+    let tmp_path = std::path::PathBuf::from(tmp_path);
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut tmp = options.open(&tmp_path)?;
+    tmp.write_all(bytes)?;
+    tmp.sync_all()?;
+    std::fs::rename(&tmp_path, path)?;
+
+    #[cfg(unix)]
+    {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => std::path::Path::new("."),
+        };
+        std::fs::File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+// Read a file holding the encoding of `T`, or `None` if there is no such file
+// yet. The read bytes are zeroized, as they may hold secrets.
+pub(crate) fn read_encoded<T>(path: &std::path::Path) -> Result<Option<T>, FileError>
+where
+    T: for<'a> tezos_data_encoding::nom::NomReader<'a>,
+{
+    use zeroize::Zeroize;
+
+    let mut bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let value = match T::nom_read(&bytes) {
+        Ok((&[], value)) => Ok(Some(value)),
+        _ => Err(FileError::Corrupted),
+    };
+    bytes.zeroize();
+    value
+}
+
+// Encode `value` and write it atomically to a file. The encoded bytes are
+// zeroized, as they may hold secrets.
+pub(crate) fn write_encoded<T>(path: &std::path::Path, value: &T) -> Result<(), FileError>
+where
+    T: tezos_data_encoding::enc::BinWriter,
+{
+    use zeroize::Zeroize;
+
+    let mut bytes = Vec::new();
+    let written = value
+        .bin_write(&mut bytes)
+        .map_err(FileError::from)
+        .and_then(|()| write_atomically(path, &bytes).map_err(FileError::from));
+    bytes.zeroize();
+    written
 }
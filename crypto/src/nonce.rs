@@ -0,0 +1,346 @@
+// SPDX-FileCopyrightText: 2024 Trilitech <contact@trili.tech>
+//
+// SPDX-License-Identifier: MIT
+
+//! Seed nonces of bakers.
+//!
+//! When baking some blocks, a baker commits to a random nonce, with its
+//! [NonceHash] in the block header. In the next cycle, it reveals the nonce
+//! with a seed nonce revelation operation, checked against the commitment.
+//! Revealed nonces make up the random seed of the protocol.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use tezos_data_encoding::enc::BinWriter;
+use tezos_data_encoding::encoding::HasEncoding;
+use tezos_data_encoding::nom::NomReader;
+use tezos_data_encoding::types::SizedBytes;
+use thiserror::Error;
+use zeroize::Zeroize;
+
+use crate::blake2b;
+use crate::hash::{BlockHash, NonceHash};
+use crate::FileError;
+
+/// Size of a nonce, in bytes.
+pub const NONCE_SIZE: usize = 32;
+
+/// Possible errors of nonces.
+#[derive(Debug, Error)]
+pub enum NonceError {
+    /// The bytes are not a seed nonce revelation.
+    #[error("invalid seed nonce revelation")]
+    InvalidRevelation,
+    /// The nonce file could not be read or written.
+    #[error("nonce {0}")]
+    File(#[from] FileError),
+}
+
+/// Seed nonce, committed to by its hash before being revealed.
+///
+/// Until it is revealed, the nonce is secret: like secret keys, it is
+/// zeroized on drop and compared in constant time, and `Debug` only shows its
+/// hash.
+#[derive(Clone, HasEncoding, BinWriter, NomReader)]
+pub struct Nonce {
+    bytes: SizedBytes<NONCE_SIZE>,
+}
+
+impl Nonce {
+    /// Generate a random nonce.
+    pub fn generate<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + rand::CryptoRng,
+    {
+        let mut bytes = [0; NONCE_SIZE];
+        rng.fill_bytes(&mut bytes);
+        let nonce = Self::from_bytes(bytes);
+        bytes.zeroize();
+        nonce
+    }
+
+    /// Nonce of the given bytes, such as those of a seed nonce revelation.
+    pub fn from_bytes(bytes: [u8; NONCE_SIZE]) -> Self {
+        Self {
+            bytes: SizedBytes(bytes),
+        }
+    }
+
+    /// Bytes of the nonce, which are secret until it is revealed.
+    pub fn as_bytes(&self) -> &[u8; NONCE_SIZE] {
+        &self.bytes.0
+    }
+
+    /// Commitment to the nonce, for the block header.
+    pub fn hash(&self) -> NonceHash {
+        NonceHash(blake2b::digest_256(self.as_bytes()))
+    }
+
+    /// Check that the nonce is the one committed to.
+    pub fn verify(&self, commitment: &NonceHash) -> bool {
+        self.hash() == *commitment
+    }
+}
+
+impl PartialEq for Nonce {
+    fn eq(&self, other: &Self) -> bool {
+        subtle::ConstantTimeEq::ct_eq(&self.bytes.0[..], &other.bytes.0[..]).into()
+    }
+}
+
+impl Eq for Nonce {}
+
+impl Drop for Nonce {
+    fn drop(&mut self) {
+        self.bytes.0.zeroize();
+    }
+}
+
+impl zeroize::ZeroizeOnDrop for Nonce {}
+
+impl std::fmt::Debug for Nonce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Nonce").field("hash", &self.hash()).finish()
+    }
+}
+
+/// Seed nonce revelation operation, revealing the nonce committed to in the
+/// block at `level`.
+#[derive(Debug, Clone, PartialEq, Eq, HasEncoding, BinWriter, NomReader)]
+pub struct SeedNonceRevelation {
+    pub level: i32,
+    pub nonce: Nonce,
+}
+
+impl SeedNonceRevelation {
+    /// Tag of seed nonce revelations among operation contents.
+    pub const TAG: u8 = 1;
+
+    /// Binary encoding as operation contents, starting with [Self::TAG].
+    pub fn to_contents_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![Self::TAG];
+        bytes.extend_from_slice(&self.level.to_be_bytes());
+        bytes.extend_from_slice(self.nonce.as_bytes());
+        bytes
+    }
+
+    /// Decode operation contents, which must be a seed nonce revelation.
+    pub fn from_contents_bytes(bytes: &[u8]) -> Result<Self, NonceError> {
+        match bytes.split_first() {
+            Some((&Self::TAG, rest)) => match Self::nom_read(rest) {
+                Ok((&[], revelation)) => Ok(revelation),
+                _ => Err(NonceError::InvalidRevelation),
+            },
+            _ => Err(NonceError::InvalidRevelation),
+        }
+    }
+
+    /// Check that the revealed nonce is the one committed to, in the block
+    /// at [Self::level].
+    pub fn verify(&self, commitment: &NonceHash) -> bool {
+        self.nonce.verify(commitment)
+    }
+}
+
+/// Nonce committed to in a block, waiting to be revealed.
+#[derive(Debug, Clone, PartialEq, Eq, HasEncoding, BinWriter, NomReader)]
+pub struct PendingNonce {
+    pub block: BlockHash,
+    pub level: i32,
+    pub nonce: Nonce,
+}
+
+impl PendingNonce {
+    /// Operation revealing the nonce.
+    pub fn revelation(&self) -> SeedNonceRevelation {
+        SeedNonceRevelation {
+            level: self.level,
+            nonce: self.nonce.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, HasEncoding, BinWriter, NomReader)]
+struct NonceFile {
+    #[encoding(dynamic)]
+    nonces: Vec<PendingNonce>,
+}
+
+/// Store of the nonces waiting to be revealed, by block, optionally
+/// persisted to a file.
+#[derive(Debug, Default)]
+pub struct PendingNonces {
+    path: Option<PathBuf>,
+    nonces: BTreeMap<BlockHash, PendingNonce>,
+}
+
+impl PendingNonces {
+    /// In-memory store, which is lost on exit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store persisted to the given file, loading its content if it exists.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, NonceError> {
+        let path = path.as_ref().to_path_buf();
+
+        let nonces = match crate::read_encoded::<NonceFile>(&path)? {
+            Some(file) => file.nonces.into_iter().map(|n| (n.block, n)).collect(),
+            None => BTreeMap::new(),
+        };
+
+        Ok(Self {
+            path: Some(path),
+            nonces,
+        })
+    }
+
+    /// The pending nonce of a block.
+    pub fn get(&self, block: &BlockHash) -> Option<&PendingNonce> {
+        self.nonces.get(block)
+    }
+
+    /// Pending nonces, by block.
+    pub fn iter(&self) -> impl Iterator<Item = &PendingNonce> {
+        self.nonces.values()
+    }
+
+    /// Record the nonce committed to in a block.
+    ///
+    /// The store is persisted before returning, if it is backed by a file.
+    pub fn add(&mut self, block: BlockHash, level: i32, nonce: Nonce) -> Result<(), NonceError> {
+        self.nonces.insert(
+            block,
+            PendingNonce {
+                block,
+                level,
+                nonce,
+            },
+        );
+        self.persist()
+    }
+
+    /// Forget the nonce of a block, once revealed or no longer revealable.
+    ///
+    /// The store is persisted before returning, if it is backed by a file.
+    pub fn remove(&mut self, block: &BlockHash) -> Result<Option<PendingNonce>, NonceError> {
+        let nonce = self.nonces.remove(block);
+        self.persist()?;
+        Ok(nonce)
+    }
+
+    /// Revelations of the nonces of blocks up to `level`, included.
+    pub fn revelations(&self, level: i32) -> Vec<SeedNonceRevelation> {
+        self.nonces
+            .values()
+            .filter(|nonce| nonce.level <= level)
+            .map(PendingNonce::revelation)
+            .collect()
+    }
+
+    fn persist(&self) -> Result<(), NonceError> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        let file = NonceFile {
+            nonces: self.nonces.values().cloned().collect(),
+        };
+        crate::write_encoded(path, &file)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::HashTrait;
+    use std::fs;
+
+    #[test]
+    fn commitment() {
+        let nonce = Nonce::from_bytes([0; NONCE_SIZE]);
+        // blake2b-256 of 32 zero bytes
+        assert_eq!(
+            nonce.hash().as_ref(),
+            hex::decode("89eb0d6a8a691dae2cd15ed0369931ce0a949ecafa5c3f93f8121833646e15c3")
+                .unwrap()
+        );
+        assert!(nonce.hash().to_b58check().starts_with("nce"));
+        assert!(nonce.verify(&nonce.hash()));
+
+        let other = Nonce::generate(&mut rand::thread_rng());
+        assert!(!other.verify(&nonce.hash()));
+        assert!(!format!("{:?}", other).contains(&hex::encode(other.as_bytes())));
+    }
+
+    #[test]
+    fn revelation_encoding() {
+        let revelation = SeedNonceRevelation {
+            level: 0x01020304,
+            nonce: Nonce::from_bytes([7; NONCE_SIZE]),
+        };
+
+        let bytes = revelation.to_contents_bytes();
+        assert_eq!(bytes[..5], [1, 1, 2, 3, 4]);
+        assert_eq!(bytes[5..], [7; NONCE_SIZE]);
+        assert_eq!(
+            SeedNonceRevelation::from_contents_bytes(&bytes).unwrap(),
+            revelation
+        );
+        assert!(revelation.verify(&Nonce::from_bytes([7; NONCE_SIZE]).hash()));
+
+        assert!(SeedNonceRevelation::from_contents_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut wrong_tag = bytes.clone();
+        wrong_tag[0] = 2;
+        assert!(SeedNonceRevelation::from_contents_bytes(&wrong_tag).is_err());
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert!(SeedNonceRevelation::from_contents_bytes(&trailing).is_err());
+    }
+
+    #[test]
+    fn pending_nonces_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nonces");
+
+        let mut rng = rand::thread_rng();
+        let block = |i| BlockHash([i; 32]);
+
+        let mut nonces = PendingNonces::open(&path).unwrap();
+        for level in 1..=3 {
+            nonces
+                .add(block(level as u8), level, Nonce::generate(&mut rng))
+                .unwrap();
+        }
+        let revealed = nonces.remove(&block(1)).unwrap().unwrap();
+        assert_eq!(revealed.level, 1);
+
+        let reopened = PendingNonces::open(&path).unwrap();
+        assert_eq!(
+            reopened.iter().collect::<Vec<_>>(),
+            nonces.iter().collect::<Vec<_>>()
+        );
+        assert!(reopened.get(&block(1)).is_none());
+
+        let revelations = reopened.revelations(2);
+        assert_eq!(revelations.len(), 1);
+        assert_eq!(revelations[0].level, 2);
+        assert!(revelations[0].verify(&nonces.get(&block(2)).unwrap().nonce.hash()));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        fs::write(&path, [0, 0, 0, 1]).unwrap();
+        assert!(matches!(
+            PendingNonces::open(&path),
+            Err(NonceError::File(FileError::Corrupted))
+        ));
+    }
+}
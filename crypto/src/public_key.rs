@@ -5,8 +5,8 @@
 
 //! Public Key of Layer1.
 
-use crate::base58::{FromBase58Check, FromBase58CheckError};
-use crate::hash::{Hash, HashTrait, HashType};
+use crate::base58::FromBase58CheckError;
+use crate::hash::{AnyHash, Hash, HashTrait};
 use crate::hash::{PublicKeyBls, PublicKeyEd25519, PublicKeyP256, PublicKeySecp256k1};
 use crate::signature::Signature;
 use crate::{CryptoError, PublicKeySignatureVerifier};
@@ -42,19 +42,13 @@ impl Display for PublicKey {
 impl PublicKey {
     /// Conversion from base58-encoding string (with prefix).
     pub fn from_b58check(data: &str) -> Result<Self, FromBase58CheckError> {
-        let bytes = data.from_base58check()?;
-        let public_key = if bytes.starts_with(HashType::PublicKeyEd25519.base58check_prefix()) {
-            PublicKey::Ed25519(PublicKeyEd25519::from_b58check(data)?)
-        } else if bytes.starts_with(HashType::PublicKeySecp256k1.base58check_prefix()) {
-            PublicKey::Secp256k1(PublicKeySecp256k1::from_b58check(data)?)
-        } else if bytes.starts_with(HashType::PublicKeyP256.base58check_prefix()) {
-            PublicKey::P256(PublicKeyP256::from_b58check(data)?)
-        } else if bytes.starts_with(HashType::PublicKeyBls.base58check_prefix()) {
-            PublicKey::Bls(PublicKeyBls::from_b58check(data)?)
-        } else {
-            return Err(FromBase58CheckError::InvalidBase58);
-        };
-        Ok(public_key)
+        match AnyHash::from_b58check(data)? {
+            AnyHash::PublicKeyEd25519(pk) => Ok(PublicKey::Ed25519(pk)),
+            AnyHash::PublicKeySecp256k1(pk) => Ok(PublicKey::Secp256k1(pk)),
+            AnyHash::PublicKeyP256(pk) => Ok(PublicKey::P256(pk)),
+            AnyHash::PublicKeyBls(pk) => Ok(PublicKey::Bls(pk)),
+            _ => Err(FromBase58CheckError::IncorrectBase58Prefix),
+        }
     }
 
     /// Conversion to base58-encoding string (with prefix).
@@ -105,9 +99,9 @@ impl PublicKeySignatureVerifier for PublicKey {
                 secp256k1.verify_signature(&signature.try_into()?, msg)
             }
             PublicKey::P256(p256) => p256.verify_signature(&signature.try_into()?, msg),
-            #[cfg(feature = "bls")]
+            #[cfg(any(feature = "bls", feature = "bls-pure"))]
             PublicKey::Bls(bls) => bls.verify_signature(&signature.try_into()?, msg),
-            #[cfg(not(feature = "bls"))]
+            #[cfg(not(any(feature = "bls", feature = "bls-pure")))]
             PublicKey::Bls(_) => Err(CryptoError::Unsupported(
                 "bls feature disabled, tz4 signature verification not supported",
             )),
@@ -131,7 +125,6 @@ impl crate::PublicKeyWithHash for PublicKey {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::PublicKeyWithHash;
 
     #[test]
     fn tz1_b58check() {
@@ -356,8 +349,8 @@ mod test {
         assert!(!result);
     }
 
-    #[cfg_attr(feature = "bls", test)]
-    #[cfg(feature = "bls")]
+    #[cfg_attr(any(feature = "bls", feature = "bls-pure"), test)]
+    #[cfg(any(feature = "bls", feature = "bls-pure"))]
     fn tz4_signature_signature_verification_succeeds() {
         // sk: BLsk2wHXLW6gN9sbEN2rU84mmCSNZKn9KRKrw74LwHqEaLGwL3qQ31
         let tz4 = PublicKey::from_b58check(
@@ -372,8 +365,8 @@ mod test {
         assert!(result);
     }
 
-    #[cfg_attr(feature = "bls", test)]
-    #[cfg(feature = "bls")]
+    #[cfg_attr(any(feature = "bls", feature = "bls-pure"), test)]
+    #[cfg(any(feature = "bls", feature = "bls-pure"))]
     fn tz4_signature_signature_verification_fails() {
         // sk: BLsk2wHXLW6gN9sbEN2rU84mmCSNZKn9KRKrw74LwHqEaLGwL3qQ31
         let tz4 = PublicKey::from_b58check(
@@ -388,8 +381,11 @@ mod test {
         assert!(!result);
     }
 
+    #[cfg(not(feature = "mavryk"))]
     #[test]
     fn pk_hash() {
+        use crate::PublicKeyWithHash;
+
         let test_hash = |pk, pkh| {
             let pk = PublicKey::from_b58check(pk).unwrap();
             let hash = pk.pk_hash().to_b58check();
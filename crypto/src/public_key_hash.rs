@@ -9,9 +9,10 @@ use tezos_data_encoding::enc::BinWriter;
 use tezos_data_encoding::encoding::HasEncoding;
 use tezos_data_encoding::nom::NomReader;
 
-use crate::base58::{FromBase58Check, FromBase58CheckError};
+use crate::base58::FromBase58CheckError;
 use crate::hash::{
-    ContractTz1Hash, ContractTz2Hash, ContractTz3Hash, ContractTz4Hash, Hash, HashTrait, HashType,
+    AnyHash, ContractTz1Hash, ContractTz2Hash, ContractTz3Hash, ContractTz4Hash, Hash, HashTrait,
+    Network,
 };
 
 /// Hash of Layer1 contract ids.
@@ -40,32 +41,31 @@ impl Display for PublicKeyHash {
 
 impl PublicKeyHash {
     /// Conversion from base58-encoding string (with prefix).
+    ///
+    /// Accepts the prefixes of every [Network], such as `tz1` and `mv1`.
     pub fn from_b58check(data: &str) -> Result<Self, FromBase58CheckError> {
-        let bytes = data.from_base58check()?;
-        match bytes {
-            _ if bytes.starts_with(HashType::ContractTz1Hash.base58check_prefix()) => Ok(
-                PublicKeyHash::Ed25519(ContractTz1Hash::from_b58check(data)?),
-            ),
-            _ if bytes.starts_with(HashType::ContractTz2Hash.base58check_prefix()) => Ok(
-                PublicKeyHash::Secp256k1(ContractTz2Hash::from_b58check(data)?),
-            ),
-            _ if bytes.starts_with(HashType::ContractTz3Hash.base58check_prefix()) => {
-                Ok(PublicKeyHash::P256(ContractTz3Hash::from_b58check(data)?))
-            }
-            _ if bytes.starts_with(HashType::ContractTz4Hash.base58check_prefix()) => {
-                Ok(PublicKeyHash::Bls(ContractTz4Hash::from_b58check(data)?))
-            }
-            _ => Err(FromBase58CheckError::InvalidBase58),
+        match AnyHash::from_b58check(data)? {
+            AnyHash::ContractTz1Hash(tz1) => Ok(PublicKeyHash::Ed25519(tz1)),
+            AnyHash::ContractTz2Hash(tz2) => Ok(PublicKeyHash::Secp256k1(tz2)),
+            AnyHash::ContractTz3Hash(tz3) => Ok(PublicKeyHash::P256(tz3)),
+            AnyHash::ContractTz4Hash(tz4) => Ok(PublicKeyHash::Bls(tz4)),
+            _ => Err(FromBase58CheckError::IncorrectBase58Prefix),
         }
     }
 
     /// Conversion to base58-encoding string (with prefix).
     pub fn to_b58check(&self) -> String {
+        self.to_b58check_for(Network::DEFAULT)
+    }
+
+    /// Conversion to base58-encoding string, with the prefix of the given
+    /// network.
+    pub fn to_b58check_for(&self, network: Network) -> String {
         match self {
-            Self::Ed25519(tz1) => tz1.to_b58check(),
-            Self::Secp256k1(tz2) => tz2.to_b58check(),
-            Self::P256(tz3) => tz3.to_b58check(),
-            Self::Bls(tz4) => tz4.to_b58check(),
+            Self::Ed25519(tz1) => tz1.to_b58check_for(network),
+            Self::Secp256k1(tz2) => tz2.to_b58check_for(network),
+            Self::P256(tz3) => tz3.to_b58check_for(network),
+            Self::Bls(tz4) => tz4.to_b58check_for(network),
         }
     }
 }
@@ -93,6 +93,7 @@ impl TryFrom<&str> for PublicKeyHash {
 mod test {
     use super::*;
 
+    #[cfg(not(feature = "mavryk"))]
     #[test]
     fn tz1_b58check() {
         let tz1 = "tz1RjtZUVeLhADFHDL8UwDZA6vjWWhojpu5w";
@@ -106,6 +107,7 @@ mod test {
         assert_eq!(tz1, &tz1_from_pkh);
     }
 
+    #[cfg(not(feature = "mavryk"))]
     #[test]
     fn tz2_b58check() {
         let tz2 = "tz2VGBaXuS6rnaa5hpC92qkgadRJKdEbeGwc";
@@ -119,6 +121,7 @@ mod test {
         assert_eq!(tz2, &tz2_from_pkh);
     }
 
+    #[cfg(not(feature = "mavryk"))]
     #[test]
     fn tz3_b58check() {
         let tz3 = "tz3WEJYwJ6pPwVbSL8FrSoAXRmFHHZTuEnMA";
@@ -132,6 +135,7 @@ mod test {
         assert_eq!(tz3, &tz3_from_pkh);
     }
 
+    #[cfg(not(feature = "mavryk"))]
     #[test]
     fn tz4_b58check() {
         let tz4 = "tz4DWZXsrP3bdPaZ5B3M3iLVoRMAyxw9oKLH";
@@ -145,6 +149,52 @@ mod test {
         assert_eq!(tz4, &tz4_from_pkh);
     }
 
+    #[test]
+    fn mavryk_b58check() {
+        let test_cases = [
+            (
+                "tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx",
+                "mv18Cw7psUrAAPBpXYd9CtCpHg9EgjHP9KTe",
+            ),
+            (
+                "tz2VGBaXuS6rnaa5hpC92qkgadRJKdEbeGwc",
+                "mv2gyFsEX67PpjAjzDnQbyvq1Run8xXMzZT3",
+            ),
+            (
+                "tz3WEJYwJ6pPwVbSL8FrSoAXRmFHHZTuEnMA",
+                "mv3JbmrXcU7kWmNfUSphgcrYan7Fqwvfahjc",
+            ),
+            (
+                "tz4DWZXsrP3bdPaZ5B3M3iLVoRMAyxw9oKLH",
+                "mv4RDdpaU348fYBDMadccrWeEDqeoJAKXnHs",
+            ),
+        ];
+
+        for (tz, mv) in test_cases {
+            let pkh = PublicKeyHash::from_b58check(mv).expect("expected valid mv hash");
+
+            assert_eq!(pkh, PublicKeyHash::from_b58check(tz).unwrap());
+            assert_eq!(mv, pkh.to_b58check_for(Network::Mavryk));
+            assert_eq!(tz, pkh.to_b58check_for(Network::Tezos));
+        }
+    }
+
+    #[cfg(feature = "mavryk")]
+    #[test]
+    fn mv1_display_serde() {
+        let mv1 = "mv18Cw7psUrAAPBpXYd9CtCpHg9EgjHP9KTe";
+
+        let pkh = PublicKeyHash::try_from(mv1).unwrap();
+        assert_eq!(mv1, pkh.to_string());
+
+        let hash = mv1.parse::<crate::hash::ContractTz1Hash>().unwrap();
+        assert_eq!(mv1, hash.to_string());
+
+        let json = serde_json::to_string(&hash).unwrap();
+        assert_eq!(format!("\"{}\"", mv1), json);
+        assert_eq!(hash, serde_json::from_str(&json).unwrap());
+    }
+
     #[test]
     fn tz1_encoding() {
         let tz1 = "tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx";
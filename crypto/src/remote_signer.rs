@@ -0,0 +1,163 @@
+// SPDX-FileCopyrightText: 2024 Trilitech <contact@trili.tech>
+//
+// SPDX-License-Identifier: MIT
+
+//! Remote signer, speaking the octez signer HTTP protocol.
+//!
+//! - `GET /keys/<pkh>` returns the public key of `pkh`, as
+//!   `{ "public_key": "<b58check>" }`.
+//! - `POST /keys/<pkh>`, with the bytes to sign as a hex JSON string, returns
+//!   `{ "signature": "<b58check>" }`. When the signer requires
+//!   authentication, the request carries an `authentication` query parameter:
+//!   a signature of [authentication_bytes] by one of the authorized keys.
+//! - `GET /authorized_keys` returns `{}` when authentication is not required,
+//!   and `{ "authorized_keys": ["<pkh>", ...] }` otherwise.
+
+use tezos_data_encoding::enc::BinWriter;
+
+use crate::public_key_hash::PublicKeyHash;
+
+mod client;
+mod server;
+
+pub use client::{RemoteSigner, RemoteSignerError};
+pub use server::{SignerHandle, SignerResponse, SignerServer};
+
+/// Bytes signed by an authentication key, to authorize signing `data` with `pkh`.
+pub fn authentication_bytes(pkh: &PublicKeyHash, data: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0x04];
+    pkh.bin_write(&mut bytes)
+        .expect("public key hash encoding is infallible");
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::secret_key::SecretKey;
+    use crate::watermark::Watermark;
+    use crate::{PublicKeySignatureVerifier, PublicKeyWithHash};
+
+    const SK: &str = "edsk3vifWnPCr8jXyhnt1YLa5KeNYTPfHENDq9gxqAA8ERkvEigYMe";
+    const AUTH_SK: &str = "spsk1sheno8Jt8FoBEoamFoNBxUEpjEggNNpepTFc8cEoJBA9QjDJq";
+
+    #[test]
+    fn authentication_bytes_layout() {
+        let pkh = PublicKeyHash::from_b58check("tz1Ke2h7sDdakHJQh8WX4Z372du1KChsksyU").unwrap();
+
+        let bytes = authentication_bytes(&pkh, &[0x03, 0xff]);
+
+        assert_eq!(1 + 21 + 2, bytes.len());
+        assert_eq!(&[0x04, 0x00], &bytes[..2]);
+        assert_eq!(&[0x03, 0xff], &bytes[22..]);
+    }
+
+    #[test]
+    fn client_server() {
+        let sk = SecretKey::from_b58check(SK).unwrap();
+        let pk = sk.public_key().unwrap();
+        let pkh = pk.pk_hash();
+
+        let mut server = SignerServer::new();
+        server.add_key(sk).unwrap();
+        let handle = server.serve("127.0.0.1:0").unwrap();
+
+        let signer = RemoteSigner::new(format!("http://{}", handle.addr()));
+
+        assert_eq!(pk, signer.public_key(&pkh).unwrap());
+        assert_eq!(None, signer.authorized_keys().unwrap());
+
+        let msg = Watermark::GenericOperation.apply(&[0; 64]);
+        let signature = signer.sign(&pkh, &msg).unwrap();
+        assert!(pk.verify_signature(&signature, &msg).unwrap());
+
+        let unknown = PublicKeyHash::from_b58check("tz1Ke2h7sDdakHJQh8WX4Z372du1KChsksyU").unwrap();
+        assert!(matches!(
+            signer.public_key(&unknown),
+            Err(RemoteSignerError::Http { status: 404, .. })
+        ));
+
+        handle.shutdown();
+    }
+
+    #[test]
+    fn client_server_authentication() {
+        let sk = SecretKey::from_b58check(SK).unwrap();
+        let pk = sk.public_key().unwrap();
+        let pkh = pk.pk_hash();
+        let auth_sk = SecretKey::from_b58check(AUTH_SK).unwrap();
+        let auth_pk = auth_sk.public_key().unwrap();
+
+        let mut server = SignerServer::new();
+        server.add_key(sk).unwrap();
+        server.add_authorized_key(auth_pk.clone());
+        let handle = server.serve("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", handle.addr());
+
+        let msg = Watermark::GenericOperation.apply(&[1; 64]);
+
+        let signer = RemoteSigner::new(&url);
+        assert_eq!(
+            Some(vec![auth_pk.pk_hash()]),
+            signer.authorized_keys().unwrap()
+        );
+        assert!(matches!(
+            signer.sign(&pkh, &msg),
+            Err(RemoteSignerError::Http { status: 401, .. })
+        ));
+
+        let other_sk =
+            SecretKey::from_b58check("p2sk2bixvFTFTuw9HtD4ucuDsktZTcwRJ5V3gDsQauwE2VTuh6hBiP")
+                .unwrap();
+        let signer = RemoteSigner::new(&url).with_authentication_key(other_sk);
+        assert!(matches!(
+            signer.sign(&pkh, &msg),
+            Err(RemoteSignerError::Http { status: 403, .. })
+        ));
+
+        let signer = RemoteSigner::new(&url).with_authentication_key(auth_sk);
+        let signature = signer.sign(&pkh, &msg).unwrap();
+        assert!(pk.verify_signature(&signature, &msg).unwrap());
+
+        handle.shutdown();
+    }
+
+    #[test]
+    fn server_requests() {
+        let sk = SecretKey::from_b58check(SK).unwrap();
+        let pkh = sk.public_key().unwrap().pk_hash();
+
+        let mut server = SignerServer::new();
+        server.add_key(sk.clone()).unwrap();
+
+        let response = server.handle_request("GET", &format!("/keys/{}", pkh), "");
+        assert_eq!(200, response.status);
+        assert_eq!(
+            r#"{"public_key":"edpkurrsBe7UjF59ciHHmBRnS76WHx3YNL9m7owYta6ticPrdP9DG4"}"#,
+            response.body
+        );
+
+        let response = server.handle_request("POST", &format!("/keys/{}", pkh), r#""0300""#);
+        assert_eq!(200, response.status);
+        assert_eq!(
+            format!(
+                r#"{{"signature":"{}"}}"#,
+                sk.sign([0x03, 0x00]).unwrap().to_base58_check()
+            ),
+            response.body
+        );
+
+        let response = server.handle_request("POST", &format!("/keys/{}", pkh), r#""zz""#);
+        assert_eq!(400, response.status);
+
+        let response = server.handle_request("GET", "/keys/tz1", "");
+        assert_eq!(400, response.status);
+
+        let response = server.handle_request("GET", "/authorized_keys", "");
+        assert_eq!((200, "{}"), (response.status, response.body.as_str()));
+
+        let response = server.handle_request("DELETE", "/authorized_keys", "");
+        assert_eq!(404, response.status);
+    }
+}
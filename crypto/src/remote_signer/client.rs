@@ -0,0 +1,142 @@
+// SPDX-FileCopyrightText: 2024 Trilitech <contact@trili.tech>
+//
+// SPDX-License-Identifier: MIT
+
+//! Client of a remote signer.
+
+use thiserror::Error;
+
+use super::authentication_bytes;
+use crate::public_key::PublicKey;
+use crate::public_key_hash::PublicKeyHash;
+use crate::secret_key::SecretKey;
+use crate::signature::Signature;
+use crate::CryptoError;
+
+/// Possible errors of remote signer requests.
+#[derive(Debug, Error)]
+pub enum RemoteSignerError {
+    /// The signer answered with an error status.
+    #[error("signer error {status}: {body}")]
+    Http { status: u16, body: String },
+    /// The signer could not be reached.
+    #[error("signer transport error: {0}")]
+    Transport(String),
+    /// The signer answer could not be decoded.
+    #[error("invalid signer response: {0}")]
+    InvalidResponse(String),
+    /// The request could not be authenticated.
+    #[error("authentication error: {0}")]
+    Crypto(#[from] CryptoError),
+}
+
+impl From<ureq::Error> for RemoteSignerError {
+    fn from(error: ureq::Error) -> Self {
+        match error {
+            ureq::Error::Status(status, response) => Self::Http {
+                status,
+                body: response.into_string().unwrap_or_default(),
+            },
+            ureq::Error::Transport(e) => Self::Transport(e.to_string()),
+        }
+    }
+}
+
+/// Client of a signer speaking the octez signer HTTP protocol.
+#[derive(Debug)]
+pub struct RemoteSigner {
+    base_url: String,
+    authentication_key: Option<SecretKey>,
+    agent: ureq::Agent,
+}
+
+impl RemoteSigner {
+    /// Client of the signer at `base_url`, such as `http://localhost:6732`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            authentication_key: None,
+            agent: ureq::Agent::new(),
+        }
+    }
+
+    /// Authenticate signing requests with the given key.
+    pub fn with_authentication_key(mut self, authentication_key: SecretKey) -> Self {
+        self.authentication_key = Some(authentication_key);
+        self
+    }
+
+    /// Public key of `pkh`.
+    pub fn public_key(&self, pkh: &PublicKeyHash) -> Result<PublicKey, RemoteSignerError> {
+        let response = self
+            .agent
+            .get(&format!("{}/keys/{}", self.base_url, pkh))
+            .call()?;
+
+        let public_key = json_field(response, "public_key")?;
+        PublicKey::from_b58check(&public_key)
+            .map_err(|e| RemoteSignerError::InvalidResponse(e.to_string()))
+    }
+
+    /// Sign `data` with `pkh`.
+    ///
+    /// The data is signed as is, and should already be prefixed by its watermark.
+    pub fn sign(&self, pkh: &PublicKeyHash, data: &[u8]) -> Result<Signature, RemoteSignerError> {
+        let mut request = self.agent.post(&format!("{}/keys/{}", self.base_url, pkh));
+
+        if let Some(authentication_key) = &self.authentication_key {
+            let authentication = authentication_key.sign(authentication_bytes(pkh, data))?;
+            request = request.query("authentication", &authentication.to_base58_check());
+        }
+
+        let body = serde_json::Value::String(hex::encode(data)).to_string();
+        let response = request
+            .set("Content-Type", "application/json")
+            .send_string(&body)?;
+
+        let signature = json_field(response, "signature")?;
+        Signature::from_base58_check(&signature)
+            .map_err(|e| RemoteSignerError::InvalidResponse(e.to_string()))
+    }
+
+    /// Keys authorized to authenticate requests, or `None` if the signer
+    /// does not require authentication.
+    pub fn authorized_keys(&self) -> Result<Option<Vec<PublicKeyHash>>, RemoteSignerError> {
+        let response = self
+            .agent
+            .get(&format!("{}/authorized_keys", self.base_url))
+            .call()?;
+
+        let json = read_json(response)?;
+        let keys = match json.get("authorized_keys") {
+            None => return Ok(None),
+            Some(keys) => keys
+                .as_array()
+                .ok_or_else(|| RemoteSignerError::InvalidResponse(json.to_string()))?,
+        };
+
+        keys.iter()
+            .map(|key| {
+                key.as_str()
+                    .and_then(|key| PublicKeyHash::from_b58check(key).ok())
+                    .ok_or_else(|| RemoteSignerError::InvalidResponse(key.to_string()))
+            })
+            .collect::<Result<_, _>>()
+            .map(Some)
+    }
+}
+
+fn read_json(response: ureq::Response) -> Result<serde_json::Value, RemoteSignerError> {
+    let body = response
+        .into_string()
+        .map_err(|e| RemoteSignerError::Transport(e.to_string()))?;
+    serde_json::from_str(&body).map_err(|e| RemoteSignerError::InvalidResponse(e.to_string()))
+}
+
+fn json_field(response: ureq::Response, field: &str) -> Result<String, RemoteSignerError> {
+    let json = read_json(response)?;
+    json.get(field)
+        .and_then(|value| value.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| RemoteSignerError::InvalidResponse(json.to_string()))
+}
@@ -0,0 +1,239 @@
+// SPDX-FileCopyrightText: 2024 Trilitech <contact@trili.tech>
+//
+// SPDX-License-Identifier: MIT
+
+//! Embeddable signer server.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use serde_json::json;
+
+use super::authentication_bytes;
+use crate::high_watermark::{HighWatermarkError, HighWatermarks};
+use crate::public_key::PublicKey;
+use crate::public_key_hash::PublicKeyHash;
+use crate::secret_key::SecretKey;
+use crate::signature::Signature;
+use crate::{CryptoError, PublicKeySignatureVerifier, PublicKeyWithHash};
+
+/// Signer holding secret keys, answering requests of the octez signer protocol.
+#[derive(Debug, Default)]
+pub struct SignerServer {
+    keys: BTreeMap<PublicKeyHash, (PublicKey, SecretKey)>,
+    authorized_keys: Option<BTreeMap<PublicKeyHash, PublicKey>>,
+    high_watermarks: Option<HighWatermarks>,
+}
+
+/// Response to a signer request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignerResponse {
+    /// HTTP status code.
+    pub status: u16,
+    /// JSON body.
+    pub body: String,
+}
+
+impl SignerResponse {
+    fn ok(body: serde_json::Value) -> Self {
+        Self {
+            status: 200,
+            body: body.to_string(),
+        }
+    }
+
+    fn error(status: u16, msg: impl std::fmt::Display) -> Self {
+        Self {
+            status,
+            body: json!([{ "kind": "permanent", "msg": msg.to_string() }]).to_string(),
+        }
+    }
+}
+
+impl SignerServer {
+    /// Signer without keys, which does not require authentication.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a secret key to the signer.
+    pub fn add_key(&mut self, secret_key: SecretKey) -> Result<PublicKeyHash, CryptoError> {
+        let public_key = secret_key.public_key()?;
+        let pkh = public_key.pk_hash();
+        self.keys.insert(pkh.clone(), (public_key, secret_key));
+        Ok(pkh)
+    }
+
+    /// Require signing requests to be authenticated, by this key or any
+    /// other authorized key.
+    pub fn add_authorized_key(&mut self, public_key: PublicKey) {
+        self.authorized_keys
+            .get_or_insert_with(BTreeMap::new)
+            .insert(public_key.pk_hash(), public_key);
+    }
+
+    /// Refuse to sign blocks and consensus operations below the high watermarks.
+    pub fn set_high_watermarks(&mut self, high_watermarks: HighWatermarks) {
+        self.high_watermarks = Some(high_watermarks);
+    }
+
+    /// Handle a request, given its method, url (path and query) and body.
+    pub fn handle_request(&mut self, method: &str, url: &str, body: &str) -> SignerResponse {
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+        match (method, segments.as_slice()) {
+            ("GET", ["authorized_keys"]) => self.authorized_keys(),
+            ("GET", ["keys", pkh]) => match PublicKeyHash::from_b58check(pkh) {
+                Ok(pkh) => self.public_key(&pkh),
+                Err(e) => SignerResponse::error(400, e),
+            },
+            ("POST", ["keys", pkh]) => match PublicKeyHash::from_b58check(pkh) {
+                Ok(pkh) => self.sign(&pkh, query, body),
+                Err(e) => SignerResponse::error(400, e),
+            },
+            _ => SignerResponse::error(404, "not found"),
+        }
+    }
+
+    fn authorized_keys(&self) -> SignerResponse {
+        match &self.authorized_keys {
+            None => SignerResponse::ok(json!({})),
+            Some(keys) => {
+                let keys: Vec<String> = keys.keys().map(PublicKeyHash::to_b58check).collect();
+                SignerResponse::ok(json!({ "authorized_keys": keys }))
+            }
+        }
+    }
+
+    fn public_key(&self, pkh: &PublicKeyHash) -> SignerResponse {
+        match self.keys.get(pkh) {
+            Some((public_key, _)) => {
+                SignerResponse::ok(json!({ "public_key": public_key.to_b58check() }))
+            }
+            None => SignerResponse::error(404, format!("unknown key {}", pkh)),
+        }
+    }
+
+    fn sign(&mut self, pkh: &PublicKeyHash, query: &str, body: &str) -> SignerResponse {
+        let data = match serde_json::from_str::<String>(body)
+            .map_err(|e| e.to_string())
+            .and_then(|hex| hex::decode(hex).map_err(|e| e.to_string()))
+        {
+            Ok(data) => data,
+            Err(e) => return SignerResponse::error(400, e),
+        };
+
+        if let Some(authorized_keys) = &self.authorized_keys {
+            let authentication = query.split('&').find_map(|param| {
+                param
+                    .strip_prefix("authentication=")
+                    .and_then(|sig| Signature::from_base58_check(sig).ok())
+            });
+            let authentication = match authentication {
+                Some(authentication) => authentication,
+                None => return SignerResponse::error(401, "missing authentication"),
+            };
+
+            let bytes = authentication_bytes(pkh, &data);
+            let authorized = authorized_keys
+                .values()
+                .any(|key| matches!(key.verify_signature(&authentication, &bytes), Ok(true)));
+            if !authorized {
+                return SignerResponse::error(403, "unauthorized request");
+            }
+        }
+
+        let secret_key = match self.keys.get(pkh) {
+            Some((_, secret_key)) => secret_key,
+            None => return SignerResponse::error(404, format!("unknown key {}", pkh)),
+        };
+
+        let signature = match &mut self.high_watermarks {
+            Some(high_watermarks) => secret_key.sign_with_high_watermark(high_watermarks, &data),
+            None => secret_key.sign(&data).map_err(HighWatermarkError::from),
+        };
+
+        match signature {
+            Ok(signature) => {
+                SignerResponse::ok(json!({ "signature": signature.to_base58_check() }))
+            }
+            Err(e @ HighWatermarkError::BelowHighWatermark { .. }) => SignerResponse::error(409, e),
+            Err(e @ HighWatermarkError::InvalidPayload(_)) => SignerResponse::error(400, e),
+            Err(e) => SignerResponse::error(500, e),
+        }
+    }
+
+    /// Serve requests over HTTP, on a background thread.
+    ///
+    /// Use port `0` to bind to any available port, see [SignerHandle::addr].
+    pub fn serve(mut self, addr: impl ToSocketAddrs) -> io::Result<SignerHandle> {
+        let server =
+            tiny_http::Server::http(addr).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        let addr = server.server_addr().to_ip().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::Other, "signer must listen on an IP address")
+        })?;
+        let server = Arc::new(server);
+
+        let thread = {
+            let server = server.clone();
+            std::thread::spawn(move || {
+                for mut request in server.incoming_requests() {
+                    let mut body = String::new();
+                    let response = match request.as_reader().read_to_string(&mut body) {
+                        Ok(_) => {
+                            self.handle_request(request.method().as_str(), request.url(), &body)
+                        }
+                        Err(e) => SignerResponse::error(400, e),
+                    };
+
+                    let content_type =
+                        tiny_http::Header::from_bytes("Content-Type", "application/json")
+                            .expect("valid header");
+                    let _ = request.respond(
+                        tiny_http::Response::from_string(response.body)
+                            .with_status_code(response.status)
+                            .with_header(content_type),
+                    );
+                }
+            })
+        };
+
+        Ok(SignerHandle {
+            addr,
+            server,
+            thread: Some(thread),
+        })
+    }
+}
+
+/// Handle of a running signer server, which is stopped when dropped.
+pub struct SignerHandle {
+    addr: SocketAddr,
+    server: Arc<tiny_http::Server>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SignerHandle {
+    /// Address the signer listens on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Stop the signer, waiting for the request in progress to complete.
+    pub fn shutdown(self) {
+        drop(self)
+    }
+}
+
+impl Drop for SignerHandle {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,584 @@
+// SPDX-FileCopyrightText: 2024 Trilitech <contact@trili.tech>
+//
+// SPDX-License-Identifier: MIT
+
+//! Secret Key of Layer1.
+
+use crate::base58::FromBase58CheckError;
+use crate::hash::{AnyHash, HashTrait, HashType};
+use crate::hash::{
+    EncryptedSecretKeyBls, EncryptedSecretKeyEd25519, EncryptedSecretKeyP256,
+    EncryptedSecretKeySecp256k1,
+};
+use crate::hash::{SecretKeyBls, SecretKeyEd25519, SecretKeyP256, SecretKeySecp256k1};
+use crate::high_watermark::{HighWatermarkError, HighWatermarks};
+use crate::public_key::PublicKey;
+use crate::signature::Signature;
+use crate::watermark::Watermark;
+use crate::{CryptoError, PublicKeyWithHash};
+use cryptoxide::hmac::Hmac;
+use cryptoxide::mac::{Mac, MacResult};
+use cryptoxide::pbkdf2::pbkdf2;
+use cryptoxide::poly1305::Poly1305;
+use cryptoxide::salsa20::Salsa20;
+use cryptoxide::sha2::Sha512;
+use std::fmt::Display;
+use tezos_data_encoding::enc::BinWriter;
+use tezos_data_encoding::encoding::HasEncoding;
+use tezos_data_encoding::nom::NomReader;
+use zeroize::Zeroize;
+
+/// Signing algorithm of a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Curve {
+    /// Tz1 - ed25519
+    Ed25519,
+    /// Tz2 - secp256k1
+    Secp256k1,
+    /// Tz3 - p256
+    P256,
+    /// Tz4 - bls12-381
+    Bls,
+}
+
+/// Secret Key of Layer1.
+#[derive(Debug, Clone, PartialEq, Eq, HasEncoding, BinWriter, NomReader)]
+pub enum SecretKey {
+    /// Tz1 - secret key
+    Ed25519(SecretKeyEd25519),
+    /// Tz2 - secret key
+    Secp256k1(SecretKeySecp256k1),
+    /// Tz3 - secret key
+    P256(SecretKeyP256),
+    /// Tz4 - secret key
+    Bls(SecretKeyBls),
+}
+
+impl Display for SecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ed25519(tz1) => write!(f, "{}", tz1),
+            Self::Secp256k1(tz2) => write!(f, "{}", tz2),
+            Self::P256(tz3) => write!(f, "{}", tz3),
+            Self::Bls(tz4) => write!(f, "{}", tz4),
+        }
+    }
+}
+
+impl SecretKey {
+    /// Generate a random secret key for the given curve.
+    pub fn generate<R>(curve: Curve, rng: &mut R) -> Result<Self, CryptoError>
+    where
+        R: rand::RngCore + rand::CryptoRng,
+    {
+        match curve {
+            Curve::Ed25519 => Ok(Self::Ed25519(SecretKeyEd25519::generate(rng))),
+            Curve::Secp256k1 => Ok(Self::Secp256k1(SecretKeySecp256k1::generate(rng))),
+            Curve::P256 => Ok(Self::P256(SecretKeyP256::generate(rng))),
+            #[cfg(any(feature = "bls", feature = "bls-pure"))]
+            Curve::Bls => SecretKeyBls::generate(rng).map(Self::Bls),
+            #[cfg(not(any(feature = "bls", feature = "bls-pure")))]
+            Curve::Bls => Err(CryptoError::Unsupported(
+                "bls feature disabled, tz4 key generation not supported",
+            )),
+        }
+    }
+
+    /// The curve of this secret key.
+    pub fn curve(&self) -> Curve {
+        match self {
+            Self::Ed25519(_) => Curve::Ed25519,
+            Self::Secp256k1(_) => Curve::Secp256k1,
+            Self::P256(_) => Curve::P256,
+            Self::Bls(_) => Curve::Bls,
+        }
+    }
+
+    /// Conversion from base58-encoding string (with prefix).
+    pub fn from_b58check(data: &str) -> Result<Self, FromBase58CheckError> {
+        match AnyHash::from_b58check(data)? {
+            AnyHash::SecretKeyEd25519(sk) => Ok(SecretKey::Ed25519(sk)),
+            AnyHash::SecretKeySecp256k1(sk) => Ok(SecretKey::Secp256k1(sk)),
+            AnyHash::SecretKeyP256(sk) => Ok(SecretKey::P256(sk)),
+            AnyHash::SecretKeyBls(sk) => Ok(SecretKey::Bls(sk)),
+            _ => Err(FromBase58CheckError::IncorrectBase58Prefix),
+        }
+    }
+
+    /// Conversion to base58-encoding string (with prefix).
+    pub fn to_b58check(&self) -> String {
+        match self {
+            Self::Ed25519(tz1) => tz1.to_b58check(),
+            Self::Secp256k1(tz2) => tz2.to_b58check(),
+            Self::P256(tz3) => tz3.to_b58check(),
+            Self::Bls(tz4) => tz4.to_b58check(),
+        }
+    }
+
+    /// Derive the public key for the current secret key.
+    pub fn public_key(&self) -> Result<PublicKey, CryptoError> {
+        match self {
+            Self::Ed25519(sk) => sk.derive_pk().map(PublicKey::Ed25519),
+            Self::Secp256k1(sk) => sk.derive_pk().map(PublicKey::Secp256k1),
+            Self::P256(sk) => sk.derive_pk().map(PublicKey::P256),
+            #[cfg(any(feature = "bls", feature = "bls-pure"))]
+            Self::Bls(sk) => sk.derive_pk().map(PublicKey::Bls),
+            #[cfg(not(any(feature = "bls", feature = "bls-pure")))]
+            Self::Bls(_) => Err(CryptoError::Unsupported(
+                "bls feature disabled, tz4 public key derivation not supported",
+            )),
+        }
+    }
+
+    /// Sign the given data.
+    pub fn sign(&self, msg: impl AsRef<[u8]>) -> Result<Signature, CryptoError> {
+        match self {
+            Self::Ed25519(sk) => sk.sign(msg).map(Signature::Ed25519),
+            Self::Secp256k1(sk) => sk.sign(msg).map(Signature::Secp256k1),
+            Self::P256(sk) => sk.sign(msg).map(Signature::P256),
+            #[cfg(any(feature = "bls", feature = "bls-pure"))]
+            Self::Bls(sk) => sk.sign(msg).map(Signature::Bls),
+            #[cfg(not(any(feature = "bls", feature = "bls-pure")))]
+            Self::Bls(_) => Err(CryptoError::Unsupported(
+                "bls feature disabled, tz4 signing not supported",
+            )),
+        }
+    }
+
+    /// Sign `msg` prefixed by the given watermark.
+    pub fn sign_with_watermark(
+        &self,
+        watermark: &Watermark,
+        msg: impl AsRef<[u8]>,
+    ) -> Result<Signature, CryptoError> {
+        self.sign(watermark.apply(msg.as_ref()))
+    }
+
+    /// Sign watermarked `bytes`, as received by a signer, refusing to sign
+    /// blocks and consensus operations below the high watermark of this key.
+    ///
+    /// The high watermark is recorded once the bytes are signed.
+    pub fn sign_with_high_watermark(
+        &self,
+        high_watermarks: &mut HighWatermarks,
+        bytes: &[u8],
+    ) -> Result<Signature, HighWatermarkError> {
+        let pkh = self.public_key()?.pk_hash();
+        high_watermarks.check(&pkh, bytes)?;
+        let signature = self.sign(bytes)?;
+        high_watermarks.check_and_record(&pkh, bytes)?;
+        Ok(signature)
+    }
+}
+
+impl TryFrom<&str> for SecretKey {
+    type Error = FromBase58CheckError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_b58check(value)
+    }
+}
+
+/// Secret Key of Layer1, encrypted with a password, as stored by octez-client.
+#[derive(Debug, Clone, PartialEq, Eq, HasEncoding, BinWriter, NomReader)]
+pub enum EncryptedSecretKey {
+    /// Tz1 - encrypted secret key
+    Ed25519(EncryptedSecretKeyEd25519),
+    /// Tz2 - encrypted secret key
+    Secp256k1(EncryptedSecretKeySecp256k1),
+    /// Tz3 - encrypted secret key
+    P256(EncryptedSecretKeyP256),
+    /// Tz4 - encrypted secret key
+    Bls(EncryptedSecretKeyBls),
+}
+
+impl Display for EncryptedSecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ed25519(tz1) => write!(f, "{}", tz1),
+            Self::Secp256k1(tz2) => write!(f, "{}", tz2),
+            Self::P256(tz3) => write!(f, "{}", tz3),
+            Self::Bls(tz4) => write!(f, "{}", tz4),
+        }
+    }
+}
+
+impl EncryptedSecretKey {
+    /// Conversion from base58-encoding string (with prefix).
+    pub fn from_b58check(data: &str) -> Result<Self, FromBase58CheckError> {
+        match AnyHash::from_b58check(data)? {
+            AnyHash::EncryptedSecretKeyEd25519(esk) => Ok(Self::Ed25519(esk)),
+            AnyHash::EncryptedSecretKeySecp256k1(esk) => Ok(Self::Secp256k1(esk)),
+            AnyHash::EncryptedSecretKeyP256(esk) => Ok(Self::P256(esk)),
+            AnyHash::EncryptedSecretKeyBls(esk) => Ok(Self::Bls(esk)),
+            _ => Err(FromBase58CheckError::IncorrectBase58Prefix),
+        }
+    }
+
+    /// Conversion to base58-encoding string (with prefix).
+    pub fn to_b58check(&self) -> String {
+        match self {
+            Self::Ed25519(tz1) => tz1.to_b58check(),
+            Self::Secp256k1(tz2) => tz2.to_b58check(),
+            Self::P256(tz3) => tz3.to_b58check(),
+            Self::Bls(tz4) => tz4.to_b58check(),
+        }
+    }
+
+    /// Decrypt the secret key with the given password.
+    ///
+    /// Fails with [CryptoError::FailedToDecrypt] if the password is wrong.
+    pub fn decrypt(&self, password: impl AsRef<[u8]>) -> Result<SecretKey, CryptoError> {
+        let data = match self {
+            Self::Ed25519(tz1) => &tz1.0,
+            Self::Secp256k1(tz2) => &tz2.0,
+            Self::P256(tz3) => &tz3.0,
+            Self::Bls(tz4) => &tz4.0,
+        };
+        let (salt, encrypted) = data.split_at(SALT_SIZE);
+
+        let mut key = derive_encryption_key(password.as_ref(), salt);
+        let secret = secretbox_open(&key, encrypted);
+        key.zeroize();
+        let secret = secret?;
+
+        let secret_key = match self {
+            Self::Ed25519(_) => SecretKey::Ed25519(SecretKeyEd25519(secret)),
+            Self::Secp256k1(_) => SecretKey::Secp256k1(SecretKeySecp256k1(secret)),
+            Self::P256(_) => SecretKey::P256(SecretKeyP256(secret)),
+            Self::Bls(_) => SecretKey::Bls(SecretKeyBls(secret)),
+        };
+        Ok(secret_key)
+    }
+}
+
+impl TryFrom<&str> for EncryptedSecretKey {
+    type Error = FromBase58CheckError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_b58check(value)
+    }
+}
+
+impl SecretKey {
+    /// Encrypt the secret key with the given password, using a random salt.
+    ///
+    /// The result can be read back by octez-client.
+    pub fn encrypt<R>(&self, password: impl AsRef<[u8]>, rng: &mut R) -> EncryptedSecretKey
+    where
+        R: rand::RngCore + rand::CryptoRng,
+    {
+        let mut salt = [0; SALT_SIZE];
+        rng.fill_bytes(&mut salt);
+
+        let secret = match self {
+            Self::Ed25519(tz1) => &tz1.0,
+            Self::Secp256k1(tz2) => &tz2.0,
+            Self::P256(tz3) => &tz3.0,
+            Self::Bls(tz4) => &tz4.0,
+        };
+
+        let mut key = derive_encryption_key(password.as_ref(), &salt);
+        let encrypted = secretbox_seal(&key, secret);
+        key.zeroize();
+
+        // The same size for every curve.
+        let mut data = [0; HashType::EncryptedSecretKeyEd25519.size()];
+        data[..SALT_SIZE].copy_from_slice(&salt);
+        data[SALT_SIZE..].copy_from_slice(&encrypted);
+
+        match self {
+            Self::Ed25519(_) => EncryptedSecretKey::Ed25519(EncryptedSecretKeyEd25519(data)),
+            Self::Secp256k1(_) => EncryptedSecretKey::Secp256k1(EncryptedSecretKeySecp256k1(data)),
+            Self::P256(_) => EncryptedSecretKey::P256(EncryptedSecretKeyP256(data)),
+            Self::Bls(_) => EncryptedSecretKey::Bls(EncryptedSecretKeyBls(data)),
+        }
+    }
+}
+
+// Parameters used by octez-client for encrypted keys.
+const SALT_SIZE: usize = 8;
+const PBKDF2_ITERATIONS: u32 = 32768;
+const MAC_SIZE: usize = 16;
+// A zero nonce is safe to use, as every key is derived with a fresh salt.
+const NONCE: [u8; 24] = [0; 24];
+
+// PBKDF2-HMAC-SHA512
+fn derive_encryption_key(password: &[u8], salt: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::new(Sha512::new(), password);
+    let mut key = [0; 32];
+    pbkdf2(&mut mac, salt, PBKDF2_ITERATIONS, &mut key);
+    key
+}
+
+// NaCl secretbox (xsalsa20poly1305), output is MAC || ciphertext.
+fn secretbox_seal(key: &[u8; 32], msg: &[u8]) -> Vec<u8> {
+    let mut cipher = Salsa20::new_xsalsa20(key, &NONCE);
+
+    // The first 32 bytes of the keystream are the one-time poly1305 key.
+    let mut mac_key = [0; 32];
+    cipher.process(&[0; 32], &mut mac_key);
+
+    let mut boxed = vec![0; MAC_SIZE + msg.len()];
+    cipher.process(msg, &mut boxed[MAC_SIZE..]);
+
+    let mut mac = Poly1305::new(&mac_key);
+    mac.input(&boxed[MAC_SIZE..]);
+    mac.raw_result(&mut boxed[..MAC_SIZE]);
+    mac_key.zeroize();
+
+    boxed
+}
+
+fn secretbox_open(key: &[u8; 32], boxed: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if boxed.len() < MAC_SIZE {
+        return Err(CryptoError::FailedToDecrypt);
+    }
+    let (tag, ciphertext) = boxed.split_at(MAC_SIZE);
+
+    let mut cipher = Salsa20::new_xsalsa20(key, &NONCE);
+
+    let mut mac_key = [0; 32];
+    cipher.process(&[0; 32], &mut mac_key);
+
+    let mut mac = Poly1305::new(&mac_key);
+    mac.input(ciphertext);
+    mac_key.zeroize();
+
+    if mac.result() != MacResult::new(tag) {
+        return Err(CryptoError::FailedToDecrypt);
+    }
+
+    let mut msg = vec![0; ciphertext.len()];
+    cipher.process(ciphertext, &mut msg);
+    Ok(msg)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PublicKeySignatureVerifier;
+
+    #[test]
+    fn tz1_b58check() {
+        let tz1 = "edsk3vifWnPCr8jXyhnt1YLa5KeNYTPfHENDq9gxqAA8ERkvEigYMe";
+
+        let sk = SecretKey::from_b58check(tz1);
+
+        assert!(matches!(sk, Ok(SecretKey::Ed25519(_))));
+
+        let tz1_from_sk = sk.unwrap().to_b58check();
+
+        assert_eq!(tz1, &tz1_from_sk);
+    }
+
+    #[test]
+    fn tz2_b58check() {
+        let tz2 = "spsk1sheno8Jt8FoBEoamFoNBxUEpjEggNNpepTFc8cEoJBA9QjDJq";
+
+        let sk = SecretKey::from_b58check(tz2);
+
+        assert!(matches!(sk, Ok(SecretKey::Secp256k1(_))));
+
+        let tz2_from_sk = sk.unwrap().to_b58check();
+
+        assert_eq!(tz2, &tz2_from_sk);
+    }
+
+    #[test]
+    fn tz3_b58check() {
+        let tz3 = "p2sk2bixvFTFTuw9HtD4ucuDsktZTcwRJ5V3gDsQauwE2VTuh6hBiP";
+
+        let sk = SecretKey::from_b58check(tz3);
+
+        assert!(matches!(sk, Ok(SecretKey::P256(_))));
+
+        let tz3_from_sk = sk.unwrap().to_b58check();
+
+        assert_eq!(tz3, &tz3_from_sk);
+    }
+
+    #[test]
+    fn tz4_b58check() {
+        let tz4 = "BLsk2wHXLW6gN9sbEN2rU84mmCSNZKn9KRKrw74LwHqEaLGwL3qQ31";
+
+        let sk = SecretKey::from_b58check(tz4);
+
+        assert!(matches!(sk, Ok(SecretKey::Bls(_))));
+
+        let tz4_from_sk = sk.unwrap().to_b58check();
+
+        assert_eq!(tz4, &tz4_from_sk);
+    }
+
+    #[test]
+    fn encoding() {
+        let test_encoding = |sk, tag| {
+            let sk = SecretKey::from_b58check(sk).expect("expected valid secret key");
+
+            let mut bin = Vec::new();
+            sk.bin_write(&mut bin).expect("serialization should work");
+
+            let deserde_sk = NomReader::nom_read(bin.as_slice())
+                .expect("deserialization should work")
+                .1;
+
+            // Check tag encoding
+            assert_eq!(tag, bin[0]);
+            assert_eq!(sk, deserde_sk);
+        };
+
+        test_encoding("edsk3vifWnPCr8jXyhnt1YLa5KeNYTPfHENDq9gxqAA8ERkvEigYMe", 0);
+        test_encoding("spsk1sheno8Jt8FoBEoamFoNBxUEpjEggNNpepTFc8cEoJBA9QjDJq", 1);
+        test_encoding("p2sk2bixvFTFTuw9HtD4ucuDsktZTcwRJ5V3gDsQauwE2VTuh6hBiP", 2);
+        test_encoding("BLsk2wHXLW6gN9sbEN2rU84mmCSNZKn9KRKrw74LwHqEaLGwL3qQ31", 3);
+    }
+
+    #[test]
+    fn public_key() {
+        let test_public_key = |sk, pk| {
+            let sk = SecretKey::from_b58check(sk).unwrap();
+            let pk_from_sk = sk.public_key().unwrap().to_b58check();
+
+            assert_eq!(pk, pk_from_sk);
+        };
+
+        test_public_key(
+            "edsk3vifWnPCr8jXyhnt1YLa5KeNYTPfHENDq9gxqAA8ERkvEigYMe",
+            "edpkurrsBe7UjF59ciHHmBRnS76WHx3YNL9m7owYta6ticPrdP9DG4",
+        );
+        test_public_key(
+            "spsk1sheno8Jt8FoBEoamFoNBxUEpjEggNNpepTFc8cEoJBA9QjDJq",
+            "sppk7a2WEfU54QzcQZ2EMjihtcxLeRtNTVxHw4FW2e8W5kEJ8ZargSb",
+        );
+        test_public_key(
+            "p2sk2bixvFTFTuw9HtD4ucuDsktZTcwRJ5V3gDsQauwE2VTuh6hBiP",
+            "p2pk65p7HKSGvkMdeK5yckM2nmi59oGNw4ksqdcvwxxF3AV3hopkfGS",
+        );
+    }
+
+    #[test]
+    fn tz1_sign() {
+        let sk = SecretKey::from_b58check("edsk3vifWnPCr8jXyhnt1YLa5KeNYTPfHENDq9gxqAA8ERkvEigYMe")
+            .unwrap();
+
+        let sig = sk.sign(b"hello, world").unwrap();
+
+        assert_eq!(
+            "edsigtoeXp3xFtGugwCTDSDuifQ9Ka81X4gXFoxRQ6Xao2Ryc3yioptrKMfNy5c9pHhbA9Xn3sYZdx2SPiCGTFXjjXx9xKCPDoq",
+            sig.to_base58_check()
+        );
+    }
+
+    #[cfg(any(feature = "bls", feature = "bls-pure"))]
+    #[test]
+    fn tz4_public_key() {
+        let (sk, pk) = crate::bls::keypair_from_ikm([7; 32]).unwrap();
+
+        let sk = SecretKey::Bls(sk);
+
+        assert_eq!(PublicKey::Bls(pk), sk.public_key().unwrap());
+    }
+
+    #[test]
+    fn generate_sign_verify() {
+        let mut rng = rand::thread_rng();
+        let msg = b"hello, generated key";
+
+        let mut curves = vec![Curve::Ed25519, Curve::Secp256k1, Curve::P256];
+        if cfg!(any(feature = "bls", feature = "bls-pure")) {
+            curves.push(Curve::Bls);
+        }
+
+        for curve in curves {
+            let sk = SecretKey::generate(curve, &mut rng).unwrap();
+            assert_eq!(curve, sk.curve());
+
+            let pk = sk.public_key().unwrap();
+            let sig = sk.sign(msg).unwrap();
+
+            assert!(pk.verify_signature(&sig, msg).unwrap());
+        }
+    }
+
+    #[test]
+    fn sign_with_watermark() {
+        let chain_id = crate::hash::ChainId::from_b58check("NetXdQprcVkpaWU").unwrap();
+        let msg = b"block header";
+
+        for sk in [
+            "edsk3vifWnPCr8jXyhnt1YLa5KeNYTPfHENDq9gxqAA8ERkvEigYMe",
+            "spsk1sheno8Jt8FoBEoamFoNBxUEpjEggNNpepTFc8cEoJBA9QjDJq",
+            "p2sk2bixvFTFTuw9HtD4ucuDsktZTcwRJ5V3gDsQauwE2VTuh6hBiP",
+        ] {
+            let sk = SecretKey::from_b58check(sk).unwrap();
+            let pk = sk.public_key().unwrap();
+
+            let watermark = Watermark::Block(chain_id);
+            let sig = sk.sign_with_watermark(&watermark, msg).unwrap();
+
+            assert_eq!(sig, sk.sign(watermark.apply(msg)).unwrap());
+            assert!(pk.verify_with_watermark(&watermark, &sig, msg).unwrap());
+
+            let other_watermark = Watermark::Attestation(chain_id);
+            assert!(!matches!(
+                pk.verify_with_watermark(&other_watermark, &sig, msg),
+                Ok(true)
+            ));
+        }
+    }
+
+    #[test]
+    fn decrypt() {
+        let encrypted = EncryptedSecretKey::from_b58check(
+            "spesk1RzvxguVHK8jxwdfbJThtwLsjRGfkxjQeWP7KExihhJp2XB8tk5hLmY3k1JSa2kk766BSxhMVMGiDuP2h6v",
+        )
+        .unwrap();
+
+        let sk = encrypted.decrypt("foobar").unwrap();
+
+        assert_eq!(
+            "spsk1sheno8Jt8FoBEoamFoNBxUEpjEggNNpepTFc8cEoJBA9QjDJq",
+            sk.to_b58check()
+        );
+
+        assert!(matches!(
+            encrypted.decrypt("barfoo"),
+            Err(CryptoError::FailedToDecrypt)
+        ));
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let test_encrypt = |sk, prefix| {
+            let sk = SecretKey::from_b58check(sk).unwrap();
+
+            let encrypted = sk.encrypt("password", &mut rand::thread_rng());
+            let encrypted_b58 = encrypted.to_b58check();
+
+            assert!(encrypted_b58.starts_with(prefix));
+            assert_eq!(88, encrypted_b58.len());
+
+            let encrypted = EncryptedSecretKey::from_b58check(&encrypted_b58).unwrap();
+
+            assert_eq!(sk, encrypted.decrypt("password").unwrap());
+        };
+
+        test_encrypt(
+            "edsk3vifWnPCr8jXyhnt1YLa5KeNYTPfHENDq9gxqAA8ERkvEigYMe",
+            "edesk",
+        );
+        test_encrypt(
+            "spsk1sheno8Jt8FoBEoamFoNBxUEpjEggNNpepTFc8cEoJBA9QjDJq",
+            "spesk",
+        );
+        test_encrypt(
+            "p2sk2bixvFTFTuw9HtD4ucuDsktZTcwRJ5V3gDsQauwE2VTuh6hBiP",
+            "p2esk",
+        );
+        test_encrypt(
+            "BLsk2wHXLW6gN9sbEN2rU84mmCSNZKn9KRKrw74LwHqEaLGwL3qQ31",
+            "BLesk",
+        );
+    }
+}
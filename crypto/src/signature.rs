@@ -11,7 +11,7 @@
 
 use crate::base58::FromBase58CheckError;
 use crate::hash::{
-    BlsSignature, Ed25519Signature, FromBytesError, HashTrait, HashType, P256Signature,
+    AnyHash, BlsSignature, Ed25519Signature, FromBytesError, HashTrait, HashType, P256Signature,
     Secp256k1Signature, UnknownSignature,
 };
 use nom::Err;
@@ -35,18 +35,13 @@ pub enum Signature {
 
 impl Signature {
     pub fn from_base58_check(data: &str) -> Result<Self, FromBase58CheckError> {
-        if data.starts_with("edsig") {
-            Ok(Signature::Ed25519(Ed25519Signature::from_b58check(data)?))
-        } else if data.starts_with("spsig1") {
-            Ok(Signature::Secp256k1(Secp256k1Signature::from_b58check(
-                data,
-            )?))
-        } else if data.starts_with("p2sig") {
-            Ok(Signature::P256(P256Signature::from_b58check(data)?))
-        } else if data.starts_with("BLsig") {
-            Ok(Signature::Bls(BlsSignature::from_b58check(data)?))
-        } else {
-            Ok(Signature::Unknown(UnknownSignature::from_b58check(data)?))
+        match AnyHash::from_b58check(data)? {
+            AnyHash::Ed25519Signature(s) => Ok(Signature::Ed25519(s)),
+            AnyHash::Secp256k1Signature(s) => Ok(Signature::Secp256k1(s)),
+            AnyHash::P256Signature(s) => Ok(Signature::P256(s)),
+            AnyHash::BlsSignature(s) => Ok(Signature::Bls(s)),
+            AnyHash::UnknownSignature(s) => Ok(Signature::Unknown(s)),
+            _ => Err(FromBase58CheckError::IncorrectBase58Prefix),
         }
     }
 
@@ -0,0 +1,94 @@
+// SPDX-FileCopyrightText: 2024 Trilitech <contact@trili.tech>
+//
+// SPDX-License-Identifier: MIT
+
+//! Signing watermarks.
+//!
+//! Every message signed by a Tezos baker or client is prefixed by a
+//! watermark, which identifies the kind of data being signed. This prevents
+//! a signature on one kind of data from being replayed as another.
+
+use crate::hash::ChainId;
+
+/// Watermark prepended to the bytes of a message before signing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Watermark {
+    /// Block header, `0x11` followed by the chain id.
+    Block(ChainId),
+    /// Preattestation, `0x12` followed by the chain id.
+    PreAttestation(ChainId),
+    /// Attestation, `0x13` followed by the chain id.
+    Attestation(ChainId),
+    /// Generic (manager) operation, `0x03`.
+    GenericOperation,
+    /// Packed Michelson data, `0x05`.
+    MichelsonData,
+    /// Arbitrary watermark bytes.
+    Custom(Vec<u8>),
+}
+
+impl Watermark {
+    /// Tag of block watermarks.
+    pub const BLOCK_TAG: u8 = 0x11;
+    /// Tag of preattestation watermarks.
+    pub const PRE_ATTESTATION_TAG: u8 = 0x12;
+    /// Tag of attestation watermarks.
+    pub const ATTESTATION_TAG: u8 = 0x13;
+    /// Tag of generic operation watermarks.
+    pub const GENERIC_OPERATION_TAG: u8 = 0x03;
+    /// Tag of Michelson data watermarks.
+    pub const MICHELSON_DATA_TAG: u8 = 0x05;
+
+    /// The watermark bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let with_chain_id = |tag: u8, chain_id: &ChainId| {
+            let mut bytes = Vec::with_capacity(1 + chain_id.0.len());
+            bytes.push(tag);
+            bytes.extend_from_slice(&chain_id.0);
+            bytes
+        };
+
+        match self {
+            Self::Block(chain_id) => with_chain_id(Self::BLOCK_TAG, chain_id),
+            Self::PreAttestation(chain_id) => with_chain_id(Self::PRE_ATTESTATION_TAG, chain_id),
+            Self::Attestation(chain_id) => with_chain_id(Self::ATTESTATION_TAG, chain_id),
+            Self::GenericOperation => vec![Self::GENERIC_OPERATION_TAG],
+            Self::MichelsonData => vec![Self::MICHELSON_DATA_TAG],
+            Self::Custom(bytes) => bytes.clone(),
+        }
+    }
+
+    /// The message prefixed by the watermark, as it is actually signed.
+    pub fn apply(&self, msg: &[u8]) -> Vec<u8> {
+        let mut bytes = self.to_bytes();
+        bytes.extend_from_slice(msg);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hash::HashTrait;
+
+    #[test]
+    fn watermark_bytes() {
+        let chain_id = ChainId::from_b58check("NetXdQprcVkpaWU").unwrap();
+
+        assert_eq!(
+            vec![0x11, 0x7a, 0x06, 0xa7, 0x70],
+            Watermark::Block(chain_id).to_bytes()
+        );
+        assert_eq!(
+            vec![0x12, 0x7a, 0x06, 0xa7, 0x70],
+            Watermark::PreAttestation(chain_id).to_bytes()
+        );
+        assert_eq!(
+            vec![0x13, 0x7a, 0x06, 0xa7, 0x70, 0xff],
+            Watermark::Attestation(chain_id).apply(&[0xff])
+        );
+        assert_eq!(vec![0x03], Watermark::GenericOperation.to_bytes());
+        assert_eq!(vec![0x05, 0x00], Watermark::MichelsonData.apply(&[0x00]));
+        assert_eq!(vec![0x42], Watermark::Custom(vec![0x42]).to_bytes());
+    }
+}